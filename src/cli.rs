@@ -4,8 +4,8 @@ Creates command-line configuration and abstracts the execution of commands using
 
 use crate::{
     error::Error,
-    reader::{GrammarFile, InputFile, NodeTypesFile},
-    writer::{Arguments, ConstantsFile, ForLanguage, Output, WrapperFile},
+    reader::{GrammarSources, InputFile, NodeTypesFile},
+    writer::{Arguments, AstFile, ConstantsFile, ForLanguage, Output, ValueMode, WrapperFile},
 };
 use clap::{Args, CommandFactory, Parser, Subcommand};
 use clap_mangen::Man;
@@ -55,9 +55,12 @@ enum Commands {
     #[command(hide = true)]
     ManPage,
     /// Create a constants file from node-types.json
-    Constants(GenerateArgs),
-    /// Create a type-safe wrapper around the tree-sitter CST using grammar.json
-    Wrapper(GenerateArgs),
+    Constants(ConstantsArgs),
+    /// Create a type-safe wrapper around the tree-sitter CST using grammar.json and node-types.json
+    Wrapper(WrapperArgs),
+    /// Create an owned AST, with lowering from the typed wrapper, using grammar.json and
+    /// node-types.json
+    Ast(AstArgs),
     /// Generate shell completions
     Completions {
         /// The shell to generate the completions for
@@ -81,6 +84,69 @@ struct GenerateArgs {
     output_directory: Option<PathBuf>,
 }
 
+#[derive(Debug, Args)]
+struct ConstantsArgs {
+    #[command(flatten)]
+    generate: GenerateArgs,
+
+    /// Also generate a `NodeKind` enum covering every named, super-type, and anonymous kind.
+    #[arg(long)]
+    node_kinds: bool,
+
+    /// Also generate a `FieldName` enum which looks up the `FieldId` of each field.
+    #[arg(long)]
+    field_ids: bool,
+
+    /// Also generate a table of node kind ids, looked up from a `Language` at runtime, and an
+    /// `is_<kind>` predicate for each named kind that compares ids.
+    #[arg(long)]
+    kind_ids: bool,
+}
+
+#[derive(Debug, Args)]
+struct WrapperArgs {
+    #[command(flatten)]
+    generate: GenerateArgs,
+
+    /// Generate a single shared type for fields and children that accept identical node types.
+    #[arg(long)]
+    share_choices: bool,
+
+    /// Generate accessors that return a `Result` rather than panicking on malformed trees.
+    #[arg(long)]
+    fallible: bool,
+
+    /// How value nodes hold terminal text, either "owned" or "borrowed". Default: "owned".
+    #[arg(long)]
+    values: Option<ValueMode>,
+
+    /// Map a terminal node kind to a Rust type implementing `FromStr`, as `KIND=TYPE` (for
+    /// example `integer=i64`); may be repeated.
+    #[arg(long = "value-type", value_name = "KIND=TYPE", value_parser = parse_value_type)]
+    value_types: Vec<(String, String)>,
+
+    /// Find fields by id using the `FieldName` enum in a constants file generated with
+    /// `--field-ids`, rather than by name.
+    #[arg(long)]
+    field_ids: bool,
+
+    /// Generate wrappers that share ownership of the tree and source, rather than borrowing them,
+    /// so that they are `Send + Sync + 'static`.
+    #[arg(long)]
+    shared_tree: bool,
+}
+
+#[derive(Debug, Args)]
+struct AstArgs {
+    /// These must match the options used to generate the wrapper the AST is lowered from.
+    #[command(flatten)]
+    wrapper: WrapperArgs,
+
+    /// Derive `serde::Serialize` and `serde::Deserialize` for all AST types.
+    #[arg(long)]
+    serde: bool,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum TracingError {
     EnvFilterParse(String),
@@ -126,13 +192,17 @@ impl Command for Commands {
             Self::Completions { shell } => {
                 shell.generate(&mut Cli::command(), &mut std::io::stdout())
             }
-            Self::Constants(args) => {
+            Self::Constants(constants_args) => {
+                let args = &constants_args.generate;
                 let input_file_name = NodeTypesFile::file_path(args.input_directory.as_ref());
                 info!("Read source from {input_file_name:?}");
                 let input = NodeTypesFile::from_file(input_file_name)?;
 
                 let for_language = args.for_language.unwrap_or_default();
-                let arguments = Arguments::new(input, for_language, args.output_directory.clone());
+                let arguments = Arguments::new(input, for_language, args.output_directory.clone())
+                    .with_node_kinds(constants_args.node_kinds)
+                    .with_field_ids(constants_args.field_ids)
+                    .with_kind_ids(constants_args.kind_ids);
                 info!("Created arguments {arguments:#?}");
 
                 let output = ConstantsFile;
@@ -142,22 +212,36 @@ impl Command for Commands {
                 output.write_to_file(arguments, file_name.clone())?;
                 println!("Node constants file written to {file_name:?}");
             }
-            Self::Wrapper(args) => {
-                let input_file_name = GrammarFile::file_path(args.input_directory.as_ref());
-                info!("Read source from {input_file_name:?}");
-                let input = GrammarFile::from_file(input_file_name)?;
-
-                let for_language = args.for_language.unwrap_or_default();
-                let arguments = Arguments::new(input, for_language, args.output_directory.clone());
+            Self::Wrapper(wrapper_args) => {
+                let arguments = wrapper_args.arguments()?;
                 info!("Created arguments {arguments:#?}");
 
                 let output = WrapperFile;
-                let file_name = output.file_path(for_language, args.output_directory.as_ref());
+                let args = &wrapper_args.generate;
+                let file_name = output.file_path(
+                    args.for_language.unwrap_or_default(),
+                    args.output_directory.as_ref(),
+                );
                 info!("Will write to file {file_name:?}");
 
                 output.write_to_file(arguments, file_name.clone())?;
                 println!("Node wrapper file written to {file_name:?}");
             }
+            Self::Ast(ast_args) => {
+                let arguments = ast_args.wrapper.arguments()?.with_serde(ast_args.serde);
+                info!("Created arguments {arguments:#?}");
+
+                let output = AstFile;
+                let args = &ast_args.wrapper.generate;
+                let file_name = output.file_path(
+                    args.for_language.unwrap_or_default(),
+                    args.output_directory.as_ref(),
+                );
+                info!("Will write to file {file_name:?}");
+
+                output.write_to_file(arguments, file_name.clone())?;
+                println!("AST file written to {file_name:?}");
+            }
         }
         Ok(ExitCode::SUCCESS)
     }
@@ -165,6 +249,28 @@ impl Command for Commands {
 
 // ------------------------------------------------------------------------------------------------
 
+impl WrapperArgs {
+    fn arguments(&self) -> Result<Arguments<GrammarSources>, Error> {
+        let args = &self.generate;
+        let input_file_name = GrammarSources::file_path(args.input_directory.as_ref());
+        info!("Read source from {input_file_name:?}");
+        let input = GrammarSources::from_file(input_file_name)?;
+
+        let for_language = args.for_language.unwrap_or_default();
+        Ok(
+            Arguments::new(input, for_language, args.output_directory.clone())
+                .with_share_choices(self.share_choices)
+                .with_fallible(self.fallible)
+                .with_value_mode(self.values.unwrap_or_default())
+                .with_value_types(self.value_types.iter().cloned())
+                .with_field_ids(self.field_ids)
+                .with_shared_tree(self.shared_tree),
+        )
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
 impl Display for TracingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(
@@ -196,6 +302,15 @@ impl From<SetGlobalDefaultError> for TracingError {
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
+fn parse_value_type(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((kind, type_name)) if !kind.trim().is_empty() && !type_name.trim().is_empty() => {
+            Ok((kind.trim().to_string(), type_name.trim().to_string()))
+        }
+        _ => Err(format!("expected `KIND=TYPE`, found `{s}`")),
+    }
+}
+
 fn initialize_tracing(level: LevelFilter, this_name: Option<&str>) -> Result<(), TracingError> {
     let mut filter = EnvFilter::from_default_env();
 
@@ -116,6 +116,21 @@ pub enum GrammarRule {
     Blank,
 }
 
+///
+/// A literal keyword that a node may or may not contain, found by [`GrammarRule::keywords`].
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Keyword<'a> {
+    /// A single literal which may be omitted, as in `optional("abstract")`.
+    Optional(&'a str),
+    /// A choice between alternative literal spellings, as in `choice("and", "∧")`, which may
+    /// itself be omitted.
+    Alternatives {
+        values: Vec<&'a str>,
+        optional: bool,
+    },
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations ❱ GrammarFile
 // ------------------------------------------------------------------------------------------------
@@ -189,6 +204,334 @@ impl GrammarFile {
     pub fn word(&self) -> Option<&Identifier> {
         self.word.as_ref()
     }
+
+    pub fn rule(&self, name: &str) -> Option<&GrammarRule> {
+        name.parse::<Identifier>()
+            .ok()
+            .and_then(|name| self.rules.get(&name))
+    }
+
+    ///
+    /// Returns `true` if the rule `name` is hidden, its name starts with an underscore and it is
+    /// not a super-type; hidden rules never appear as nodes in a parsed tree.
+    ///
+    pub fn is_hidden_rule(&self, name: &str) -> bool {
+        name.starts_with('_') && !self.is_super_type(name)
+    }
+
+    ///
+    /// Returns `true` if the rule `name` is listed in `inline`; inlined rules are replaced by
+    /// their content wherever they are used and so never appear as nodes in a parsed tree.
+    ///
+    pub fn is_inlined_rule(&self, name: &str) -> bool {
+        self.inline.iter().any(|rule| rule.to_string() == name)
+    }
+
+    pub fn is_super_type(&self, name: &str) -> bool {
+        self.supertypes.iter().any(|rule| rule.to_string() == name)
+    }
+
+    ///
+    /// Returns the rules that produce nodes of the given `kind` in a parsed tree. This is the rule
+    /// named `kind`, if it is visible, and the content of any named alias whose value is `kind`.
+    /// Within each rule any reference to a hidden or inlined rule is replaced by that rule's
+    /// content, so that the fields of these rules are hoisted into the visible node.
+    ///
+    pub fn visible_rules(&self, kind: &str) -> Vec<GrammarRule> {
+        let direct = self
+            .rule(kind)
+            .filter(|_| !self.is_hidden_rule(kind) && !self.is_inlined_rule(kind));
+        let aliased = self
+            .rules
+            .values()
+            .chain(self.extras.iter())
+            .flat_map(|rule| rule.aliases())
+            .filter_map(|(value, named, content)| {
+                if named && value.to_string() == kind {
+                    match content {
+                        GrammarRule::Symbol { name } => self.rule(name.as_ref()),
+                        _ => Some(content),
+                    }
+                } else {
+                    None
+                }
+            });
+        let mut rules: Vec<GrammarRule> = Vec::new();
+        for rule in direct.into_iter().chain(aliased) {
+            let rule = self.resolve_hidden(rule, &mut Vec::new());
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+        }
+        rules
+    }
+
+    ///
+    /// Returns the names of all fields of nodes of the given `kind`, in the order they first
+    /// appear in the grammar, including those hoisted from hidden and inlined rules.
+    ///
+    pub fn visible_field_names(&self, kind: &str) -> Vec<String> {
+        let mut field_names: Vec<String> = Vec::new();
+        for rule in self.visible_rules(kind) {
+            for name in rule.field_names() {
+                let name = name.to_string();
+                if !field_names.contains(&name) {
+                    field_names.push(name);
+                }
+            }
+        }
+        field_names
+    }
+
+    fn resolve_hidden(&self, rule: &GrammarRule, stack: &mut Vec<String>) -> GrammarRule {
+        match rule {
+            GrammarRule::Symbol { name } => {
+                let name = name.to_string();
+                if !(self.is_hidden_rule(&name) || self.is_inlined_rule(&name)) {
+                    rule.clone()
+                } else if stack.contains(&name) {
+                    // a recursive reference adds no fields that have not already been seen.
+                    GrammarRule::Blank
+                } else {
+                    match self.rule(&name) {
+                        Some(content) => {
+                            stack.push(name);
+                            let resolved = self.resolve_hidden(content, stack);
+                            stack.pop();
+                            resolved
+                        }
+                        // external tokens have no rule.
+                        None => rule.clone(),
+                    }
+                }
+            }
+            _ => rule.map_content(|content| self.resolve_hidden(content, stack)),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ GrammarRule
+// ------------------------------------------------------------------------------------------------
+
+impl GrammarRule {
+    ///
+    /// Returns the rules directly contained within this one; this is empty for opaque rules.
+    ///
+    pub fn contents(&self) -> Vec<&GrammarRule> {
+        match self {
+            Self::Sequence { members } | Self::Choice { members } => members.iter().collect(),
+            Self::Field { content, .. }
+            | Self::Repeat { content }
+            | Self::Repeat1 { content }
+            | Self::Reserved { content, .. }
+            | Self::Precedence { content, .. }
+            | Self::PrecedenceLeftAssoc { content, .. }
+            | Self::PrecedenceRightAssoc { content, .. }
+            | Self::PrecedenceDynamic { content, .. } => vec![content.as_ref()],
+            _ => Vec::new(),
+        }
+    }
+
+    ///
+    /// Returns the names of all fields within this rule, in the order they first appear, without
+    /// following symbols or descending into opaque rules.
+    ///
+    pub fn field_names(&self) -> Vec<&Identifier> {
+        let mut field_names: Vec<&Identifier> = Vec::new();
+        self.collect_field_names(&mut field_names);
+        field_names
+    }
+
+    fn aliases(&self) -> Vec<(&Identifier, bool, &GrammarRule)> {
+        let mut aliases = Vec::new();
+        self.collect_aliases(&mut aliases);
+        aliases
+    }
+
+    ///
+    /// Returns the literal keywords within this rule, outside of any field, that a node may or
+    /// may not contain, in the order they first appear and without following symbols or
+    /// descending into opaque rules. A literal, which may be wrapped in `token` or
+    /// `token.immediate`, in a choice with other, non-literal, rules is optional; two or more
+    /// literals in a choice with nothing else are alternatives.
+    ///
+    pub fn keywords(&self) -> Vec<Keyword<'_>> {
+        let mut keywords = Vec::new();
+        self.collect_keywords(&mut keywords);
+        keywords
+    }
+
+    fn collect_keywords<'a>(&'a self, keywords: &mut Vec<Keyword<'a>>) {
+        match self {
+            Self::Field { .. } => return,
+            Self::Choice { members } => {
+                let values = members
+                    .iter()
+                    .filter_map(GrammarRule::literal)
+                    .collect::<Vec<_>>();
+                let optional = members.contains(&Self::Blank);
+                let others = members
+                    .iter()
+                    .any(|member| member.literal().is_none() && *member != Self::Blank);
+                let found = if values.is_empty() || (values.len() == 1 && !others && !optional) {
+                    Vec::new()
+                } else if values.len() == 1 || others {
+                    values.into_iter().map(Keyword::Optional).collect()
+                } else {
+                    vec![Keyword::Alternatives { values, optional }]
+                };
+                for keyword in found {
+                    if !keywords.contains(&keyword) {
+                        keywords.push(keyword);
+                    }
+                }
+            }
+            _ => {}
+        }
+        for content in self.contents() {
+            content.collect_keywords(keywords);
+        }
+    }
+
+    ///
+    /// Returns the literals within this rule, outside of any field, that every node matching the
+    /// rule contains, in the order they first appear and without following symbols or descending
+    /// into opaque rules. A literal is required where it is not within a `repeat`, and where it
+    /// is within a choice only if every alternative requires it.
+    ///
+    pub fn required_literals(&self) -> Vec<&str> {
+        let mut literals = Vec::new();
+        self.collect_required_literals(&mut literals);
+        literals
+    }
+
+    fn collect_required_literals<'a>(&'a self, literals: &mut Vec<&'a str>) {
+        if let Some(literal) = self.literal() {
+            if !literals.contains(&literal) {
+                literals.push(literal);
+            }
+            return;
+        }
+        match self {
+            Self::Field { .. } | Self::Repeat { .. } => {}
+            Self::Choice { members } => {
+                let mut alternatives = members.iter().map(GrammarRule::required_literals);
+                if let Some(first) = alternatives.next() {
+                    let alternatives = alternatives.collect::<Vec<_>>();
+                    for literal in first {
+                        if alternatives.iter().all(|other| other.contains(&literal))
+                            && !literals.contains(&literal)
+                        {
+                            literals.push(literal);
+                        }
+                    }
+                }
+            }
+            _ => {
+                for content in self.contents() {
+                    content.collect_required_literals(literals);
+                }
+            }
+        }
+    }
+
+    ///
+    /// The text of a literal, which may be wrapped in `token` or `token.immediate`.
+    ///
+    pub fn literal(&self) -> Option<&str> {
+        match self {
+            Self::String { value } => Some(value),
+            Self::Token { content } | Self::ImmediateToken { content } => match content.as_ref() {
+                Self::String { value } => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn collect_field_names<'a>(&'a self, field_names: &mut Vec<&'a Identifier>) {
+        if let Self::Field { name, .. } = self
+            && !field_names
+                .iter()
+                .any(|seen| seen.to_string() == name.to_string())
+        {
+            field_names.push(name);
+        }
+        for content in self.contents() {
+            content.collect_field_names(field_names);
+        }
+    }
+
+    fn collect_aliases<'a>(&'a self, aliases: &mut Vec<(&'a Identifier, bool, &'a GrammarRule)>) {
+        match self {
+            Self::Alias {
+                value,
+                named,
+                content,
+            } => {
+                aliases.push((value, *named, content));
+                content.collect_aliases(aliases);
+            }
+            Self::Token { content } | Self::ImmediateToken { content } => {
+                content.collect_aliases(aliases)
+            }
+            _ => {
+                for content in self.contents() {
+                    content.collect_aliases(aliases);
+                }
+            }
+        }
+    }
+
+    fn map_content<F>(&self, mut f: F) -> Self
+    where
+        F: FnMut(&GrammarRule) -> GrammarRule,
+    {
+        let mut boxed = |content: &GrammarRule| Box::new(f(content));
+        match self {
+            Self::Sequence { members } => Self::Sequence {
+                members: members.iter().map(|member| *boxed(member)).collect(),
+            },
+            Self::Choice { members } => Self::Choice {
+                members: members.iter().map(|member| *boxed(member)).collect(),
+            },
+            Self::Field { name, content } => Self::Field {
+                name: name.clone(),
+                content: boxed(content),
+            },
+            Self::Repeat { content } => Self::Repeat {
+                content: boxed(content),
+            },
+            Self::Repeat1 { content } => Self::Repeat1 {
+                content: boxed(content),
+            },
+            Self::Reserved {
+                content,
+                context_name,
+            } => Self::Reserved {
+                content: boxed(content),
+                context_name: context_name.clone(),
+            },
+            Self::Precedence { value, content } => Self::Precedence {
+                value: *value,
+                content: boxed(content),
+            },
+            Self::PrecedenceLeftAssoc { value, content } => Self::PrecedenceLeftAssoc {
+                value: *value,
+                content: boxed(content),
+            },
+            Self::PrecedenceRightAssoc { value, content } => Self::PrecedenceRightAssoc {
+                value: *value,
+                content: boxed(content),
+            },
+            Self::PrecedenceDynamic { value, content } => Self::PrecedenceDynamic {
+                value: *value,
+                content: boxed(content),
+            },
+            _ => self.clone(),
+        }
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -203,7 +546,10 @@ regex_is_valid!(pub is_valid_identifier, r"^[a-zA-Z_]\w*");
 
 #[cfg(test)]
 mod tests {
-    use crate::reader::{GrammarFile, InputFile};
+    use crate::reader::{
+        GrammarFile, InputFile,
+        grammar::{GrammarRule, Keyword},
+    };
 
     #[test]
     fn test_load_example_file() {
@@ -218,4 +564,144 @@ mod tests {
         println!("Name: {}", grammar.name());
         println!("Rules: {:?}", grammar.rule_names().collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_hidden_and_inlined_fields_are_hoisted() {
+        let grammar =
+            GrammarFile::from_file(format!("./tests/{}", GrammarFile::DEFAULT_FILE_NAME)).unwrap();
+        assert!(grammar.is_hidden_rule("_type_expression"));
+        assert!(grammar.is_inlined_rule("_type_expression"));
+        assert!(!grammar.is_hidden_rule("definition"));
+        assert!(grammar.visible_rules("_type_expression").is_empty());
+        assert_eq!(
+            grammar.visible_field_names("member_def"),
+            vec!["name", "cardinality", "target", "body"]
+        );
+    }
+
+    #[test]
+    fn test_aliased_rules_use_alias_name() {
+        let grammar: GrammarFile = serde_json::from_str(
+            r#"{
+                "$schema": "https://tree-sitter.github.io/tree-sitter/assets/schemas/grammar.schema.json",
+                "name": "example",
+                "rules": {
+                    "source_file": {
+                        "type": "REPEAT",
+                        "content": {
+                            "type": "ALIAS",
+                            "value": "pair",
+                            "named": true,
+                            "content": { "type": "SYMBOL", "name": "_key_value" }
+                        }
+                    },
+                    "_key_value": {
+                        "type": "SEQ",
+                        "members": [
+                            {
+                                "type": "FIELD",
+                                "name": "key",
+                                "content": { "type": "SYMBOL", "name": "identifier" }
+                            },
+                            { "type": "STRING", "value": "=" },
+                            { "type": "SYMBOL", "name": "_value" }
+                        ]
+                    },
+                    "_value": {
+                        "type": "FIELD",
+                        "name": "value",
+                        "content": { "type": "SYMBOL", "name": "identifier" }
+                    },
+                    "identifier": { "type": "PATTERN", "value": "[a-z]+" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(grammar.visible_field_names("source_file").is_empty());
+        assert!(grammar.visible_rules("_key_value").is_empty());
+        assert_eq!(grammar.visible_field_names("pair"), vec!["key", "value"]);
+    }
+
+    #[test]
+    fn test_optional_and_alternative_keywords() {
+        let rule: GrammarRule = serde_json::from_str(
+            r#"{
+                "type": "SEQ",
+                "members": [
+                    {
+                        "type": "CHOICE",
+                        "members": [{ "type": "STRING", "value": "abstract" }, { "type": "BLANK" }]
+                    },
+                    {
+                        "type": "CHOICE",
+                        "members": [
+                            { "type": "STRING", "value": "and" },
+                            { "type": "STRING", "value": "∧" }
+                        ]
+                    },
+                    {
+                        "type": "CHOICE",
+                        "members": [
+                            { "type": "IMMEDIATE_TOKEN", "content": { "type": "STRING", "value": "." } },
+                            { "type": "IMMEDIATE_TOKEN", "content": { "type": "STRING", "value": "∘" } }
+                        ]
+                    },
+                    {
+                        "type": "FIELD",
+                        "name": "operator",
+                        "content": {
+                            "type": "CHOICE",
+                            "members": [{ "type": "STRING", "value": "+" }, { "type": "BLANK" }]
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(rule.required_literals().is_empty());
+        assert_eq!(
+            rule.keywords(),
+            vec![
+                Keyword::Optional("abstract"),
+                Keyword::Alternatives {
+                    values: vec!["and", "∧"],
+                    optional: false
+                },
+                Keyword::Alternatives {
+                    values: vec![".", "∘"],
+                    optional: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_literals() {
+        let rule: GrammarRule = serde_json::from_str(
+            r#"{
+                "type": "SEQ",
+                "members": [
+                    { "type": "STRING", "value": "(" },
+                    {
+                        "type": "CHOICE",
+                        "members": [
+                            { "type": "SEQ", "members": [{ "type": "STRING", "value": "," }, { "type": "STRING", "value": ")" }] },
+                            { "type": "STRING", "value": ")" }
+                        ]
+                    },
+                    {
+                        "type": "REPEAT",
+                        "content": { "type": "STRING", "value": ";" }
+                    },
+                    {
+                        "type": "FIELD",
+                        "name": "operator",
+                        "content": { "type": "STRING", "value": "+" }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.required_literals(), vec!["(", ")"]);
+    }
 }
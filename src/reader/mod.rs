@@ -51,3 +51,6 @@ pub use grammar::GrammarFile;
 
 pub mod node_types;
 pub use node_types::NodeTypesFile;
+
+pub mod sources;
+pub use sources::GrammarSources;
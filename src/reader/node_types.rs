@@ -24,11 +24,15 @@ pub struct NodeTypesFile(Vec<NodeTypeDefinition>);
 pub struct NodeTypeDefinition {
     #[serde(flatten)]
     node_type: NodeType,
+    #[serde(default, skip_serializing_if = "is_false")]
+    root: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    extra: bool,
     #[serde(flatten)]
     definition_kind: NodeTypeDefinitionKind,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct NodeType {
     #[serde(rename = "type")]
     node_type: String,
@@ -118,6 +122,19 @@ impl NodeTypesFile {
         self.0.iter().filter(|defn| defn.kind().is_terminal())
     }
 
+    pub fn named_terminal_definitions(&self) -> impl Iterator<Item = &NodeTypeDefinition> {
+        self.terminal_definitions()
+            .filter(|defn| defn.node_type().is_named())
+    }
+
+    pub fn root_definition(&self) -> Option<&NodeTypeDefinition> {
+        self.0.iter().find(|defn| defn.is_root())
+    }
+
+    pub fn definition(&self, node_type: &NodeType) -> Option<&NodeTypeDefinition> {
+        self.0.iter().find(|defn| defn.node_type() == node_type)
+    }
+
     pub fn has_definitions(&self) -> bool {
         !self.0.is_empty()
     }
@@ -144,6 +161,12 @@ impl NodeTypesFile {
             .collect()
     }
 
+    pub fn named_terminal_node_type_names(&self) -> BTreeSet<&String> {
+        self.named_terminal_definitions()
+            .map(|defn| defn.node_type_name())
+            .collect()
+    }
+
     pub fn terminal_node_type_names(&self) -> BTreeSet<&String> {
         self.terminal_definitions()
             .map(|defn| defn.node_type_name())
@@ -169,6 +192,8 @@ impl NodeTypeDefinition {
     {
         Self {
             node_type,
+            root: false,
+            extra: false,
             definition_kind: definition_kind.into(),
         }
     }
@@ -189,6 +214,14 @@ impl NodeTypeDefinition {
         Self::new(NodeType::new_unnamed(node_type), definition_kind)
     }
 
+    pub fn with_root(self, root: bool) -> Self {
+        Self { root, ..self }
+    }
+
+    pub fn with_extra(self, extra: bool) -> Self {
+        Self { extra, ..self }
+    }
+
     pub fn node_type(&self) -> &NodeType {
         &self.node_type
     }
@@ -197,6 +230,14 @@ impl NodeTypeDefinition {
         self.node_type.node_type()
     }
 
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    pub fn is_extra(&self) -> bool {
+        self.extra
+    }
+
     pub fn kind(&self) -> &NodeTypeDefinitionKind {
         &self.definition_kind
     }
@@ -400,6 +441,15 @@ impl NodeChildren {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+#[inline]
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 // ------------------------------------------------------------------------------------------------
 // Unit Tests
 // ------------------------------------------------------------------------------------------------
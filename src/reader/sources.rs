@@ -0,0 +1,78 @@
+/*!
+Provides [`GrammarSources`], the pair of generated tree-sitter files that describe a grammar.
+
+ */
+
+use crate::{
+    error::Error,
+    reader::{GrammarFile, InputFile, NodeTypesFile},
+};
+use std::path::Path;
+use tracing::trace;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The combination of `grammar.json` and `node-types.json`; the grammar file is loaded from the
+/// provided path and the node types file is expected to be in the same directory.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrammarSources {
+    grammar: GrammarFile,
+    node_types: NodeTypesFile,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl InputFile for GrammarSources {
+    const DEFAULT_DIRECTORY: &str = GrammarFile::DEFAULT_DIRECTORY;
+    const DEFAULT_FILE_NAME: &str = GrammarFile::DEFAULT_FILE_NAME;
+
+    fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        trace!("GrammarSources::from_file({})", path.display());
+        let grammar = GrammarFile::from_file(path)?;
+        let node_types =
+            NodeTypesFile::from_file(path.with_file_name(NodeTypesFile::DEFAULT_FILE_NAME))?;
+        Ok(Self::new(grammar, node_types))
+    }
+}
+
+impl GrammarSources {
+    pub fn new(grammar: GrammarFile, node_types: NodeTypesFile) -> Self {
+        Self {
+            grammar,
+            node_types,
+        }
+    }
+
+    pub fn grammar(&self) -> &GrammarFile {
+        &self.grammar
+    }
+
+    pub fn node_types(&self) -> &NodeTypesFile {
+        &self.node_types
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::reader::{GrammarSources, InputFile};
+
+    #[test]
+    fn test_load_example_files() {
+        let sources =
+            GrammarSources::from_file(format!("./tests/{}", GrammarSources::DEFAULT_FILE_NAME))
+                .unwrap();
+        assert_eq!(sources.grammar().name().to_string(), "sdml");
+        assert!(sources.node_types().has_definitions());
+    }
+}
@@ -0,0 +1,735 @@
+/*!
+Generates an owned abstract syntax tree (AST) for a grammar, along with a lowering from the typed
+wrappers generated by [`WrapperFile`](super::WrapperFile).
+
+The AST mirrors the types of the wrapper: every compound node becomes a struct with one public
+member per field and keyword accessor, every super-type and choice becomes an enum, every operator
+and keyword variant becomes an enum of unit variants, and every value node becomes a new-type
+around its text. A compound node with neither fields nor children, such as one matching a pattern
+or a choice of keywords, also keeps its source text in a `text` member. AST types hold no
+lifetimes or tree-sitter types and so may outlive the parsed `Tree`; where
+[`Arguments::with_serde`] is set they also derive `serde::Serialize` and `serde::Deserialize`.
+Required and optional members that hold another struct or enum are boxed so that recursive
+grammars produce types of finite size.
+
+Lowering is generated as a `Fold` trait with a `fold_<type>` method for every wrapper type,
+named as for the wrapper's own `Fold` trait, returning that type's AST type, or a `Result` with
+the wrapper's error type where [`Arguments::with_fallible`] is set. The default methods call
+matching `fold_<type>` functions that build each AST node from the folded fields and children,
+so that an implementation may rewrite any node while lowering the rest unchanged. Each AST type
+also implements `From` (or `TryFrom`) a reference to its wrapper type, folding with the
+generated `Lowering` type which overrides no methods. As lowering calls the wrapper's accessors,
+the choice and value options given to this command must match those used to generate the
+wrapper.
+
+Every AST type implements the generated `Unparse` trait, and `Display`, writing it back out as
+source text by following the node's rule in `grammar.json`, see the `unparse` module for how the
+rule is interpreted. A `Whitespace` implementation passed to `Unparse::to_source_with` decides
+the whitespace written between tokens, and is told as each node is entered and left so that it
+may indent; by default tokens are separated by a single space.
+
+ */
+
+use crate::{
+    error::Error,
+    reader::{GrammarFile, GrammarSources},
+    writer::{
+        Arguments, Output,
+        naming::{field_ident, method_name, type_name},
+        unparse::{UnparseMembers, is_immediate},
+        wrapper::{
+            ANY_NODE, Cardinality, CompoundNode, EnumNode, OperatorNode, OperatorVariant,
+            WrapperModel,
+        },
+    },
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+use tera::Tera;
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AstFile;
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const AST_TOKEN: &str = "Token";
+
+/// The member holding the source text of a node with neither fields nor children.
+const LEAF_TEXT: &str = "text";
+
+/// Names of the folding and unparsing support types in the generated code.
+const SUPPORT_NAMES: &[&str] = &[
+    "Fold",
+    "Lowering",
+    "Printer",
+    "SingleSpace",
+    "Unparse",
+    "Whitespace",
+];
+
+/// Names in the Rust prelude used by the generated code, which AST types must not shadow.
+const PRELUDE_NAMES: &[&str] = &[
+    "AsRef",
+    "Box",
+    "Clone",
+    "Default",
+    "Err",
+    "From",
+    "Into",
+    "None",
+    "Ok",
+    "Option",
+    "PartialEq",
+    "Result",
+    "Self",
+    "Some",
+    "String",
+    "ToString",
+    "TryFrom",
+    "TryInto",
+    "Vec",
+];
+
+#[derive(Clone, Debug, Serialize)]
+struct AstStruct {
+    kind: String,
+    type_name: String,
+    cst_type: String,
+    method_suffix: String,
+    fields: Vec<AstField>,
+    /// `false` where no field is lowered by folding a child, so the fold function ignores the
+    /// folder.
+    uses_folder: bool,
+    unparse: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct AstField {
+    name: String,
+    field_type: String,
+    lowering: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct AstEnum {
+    doc: String,
+    type_name: String,
+    cst_name: String,
+    cst_type: String,
+    method_suffix: String,
+    fallible: bool,
+    variants: Vec<AstVariant>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct AstVariant {
+    name: String,
+    type_name: String,
+    lowering: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct AstOperator {
+    doc: String,
+    type_name: String,
+    cst_name: String,
+    method_suffix: String,
+    variants: Vec<OperatorVariant>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct AstValue {
+    kind: Option<String>,
+    /// `true` where the token must immediately follow the previous token.
+    immediate: bool,
+    type_name: String,
+    cst_type: String,
+    method_suffix: String,
+}
+
+///
+/// Maps the name of each wrapper type to the corresponding AST type, and records which AST
+/// types are held inline and which are lowered fallibly.
+///
+struct AstNames {
+    names: BTreeMap<String, String>,
+    inline: BTreeSet<String>,
+    fallible: BTreeSet<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl Output for AstFile {
+    const DEFAULT_FILE_NAME: &str = "ast";
+    const DEFAULT_DIRECTORY: &str = "bindings";
+    type InputFile = GrammarSources;
+
+    fn write<W>(&self, arguments: Arguments<Self::InputFile>, w: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let tera = Tera::new("templates/**/ast.*")?;
+
+        let model = WrapperModel::new(&arguments)?;
+        let names = AstNames::new(&model, arguments.fallible());
+        let grammar = arguments.input_file.grammar();
+        let rule = |kind: &str| grammar.visible_rules(kind).into_iter().next();
+
+        let (root_generics, node_generics) = if arguments.shared_tree() {
+            ("", "")
+        } else {
+            ("<'_>", "<'_, '_>")
+        };
+
+        let root_node = model
+            .root_node
+            .as_ref()
+            .map(|node| names.ast_struct(node, root_generics, arguments.fallible(), grammar));
+        let compound_nodes = model
+            .compound_nodes
+            .iter()
+            .map(|node| names.ast_struct(node, node_generics, arguments.fallible(), grammar))
+            .collect::<Vec<_>>();
+        let enum_nodes = model
+            .super_type_nodes
+            .iter()
+            .chain(model.choice_nodes.iter())
+            .map(|node| names.ast_enum(node, arguments.fallible()))
+            .collect::<Vec<_>>();
+        let operator_nodes = model
+            .operator_nodes
+            .iter()
+            .chain(model.keyword_nodes.iter())
+            .map(|node| names.ast_operator(node))
+            .collect::<Vec<_>>();
+        let value_nodes = model
+            .value_nodes
+            .iter()
+            .map(|node| AstValue {
+                kind: node.kind.clone(),
+                immediate: node
+                    .kind
+                    .as_ref()
+                    .and_then(|kind| rule(kind))
+                    .is_some_and(|rule| is_immediate(&rule)),
+                type_name: names.name(&node.type_name),
+                method_suffix: node.method_suffix.clone(),
+                cst_type: format!(
+                    "{}{}",
+                    node.type_name,
+                    if node.generics.is_empty() {
+                        ""
+                    } else {
+                        "<'_, '_>"
+                    }
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        let mut context = tera::Context::new();
+        context.insert("name", arguments.input_file.grammar().name());
+        context.insert("error_type", &model.error_type);
+        context.insert("fallible", &arguments.fallible());
+        context.insert("serde", &arguments.serde());
+        context.insert("shared_tree", &arguments.shared_tree());
+        context.insert("root_node", &root_node);
+        context.insert("compound_nodes", &compound_nodes);
+        context.insert("enum_nodes", &enum_nodes);
+        context.insert("operator_nodes", &operator_nodes);
+        context.insert("value_nodes", &value_nodes);
+
+        let rendered = tera
+            .render(&format!("ast.{}", arguments.for_language), &context)
+            .unwrap();
+        w.write_all(rendered.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl AstNames {
+    fn new(model: &WrapperModel, fallible: bool) -> Self {
+        let mut names: BTreeMap<String, String> = model
+            .root_node
+            .iter()
+            .chain(model.compound_nodes.iter())
+            .map(|node| (node.type_name.clone(), type_name(&node.kind)))
+            .chain(model.value_nodes.iter().map(|node| {
+                (
+                    node.type_name.clone(),
+                    node.kind
+                        .as_ref()
+                        .map(type_name)
+                        .unwrap_or_else(|| AST_TOKEN.to_string()),
+                )
+            }))
+            .chain(
+                model
+                    .super_type_nodes
+                    .iter()
+                    .map(|node| (node.type_name.clone(), node.type_name.clone())),
+            )
+            .map(|(cst_name, name)| {
+                if PRELUDE_NAMES.contains(&name.as_str()) || SUPPORT_NAMES.contains(&name.as_str())
+                {
+                    // fall back to the wrapper's name, which is suffixed for all but super-types.
+                    let name = if cst_name == name {
+                        format!("{name}Type")
+                    } else {
+                        cst_name.clone()
+                    };
+                    (cst_name, name)
+                } else {
+                    (cst_name, name)
+                }
+            })
+            .chain(std::iter::once((
+                ANY_NODE.to_string(),
+                ANY_NODE.to_string(),
+            )))
+            .collect();
+        let mut reserved = names
+            .values()
+            .map(String::as_str)
+            .chain(PRELUDE_NAMES.iter().copied())
+            .chain(SUPPORT_NAMES.iter().copied())
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>();
+        let enum_names = model
+            .choice_nodes
+            .iter()
+            .map(|node| &node.type_name)
+            .chain(model.operator_nodes.iter().map(|node| &node.type_name))
+            .chain(model.keyword_nodes.iter().map(|node| &node.type_name));
+        for type_name in enum_names {
+            let mut name = type_name.clone();
+            if reserved.contains(&name) {
+                name = format!("{name}Choice");
+            }
+            reserved.insert(name.clone());
+            names.insert(type_name.clone(), name);
+        }
+        let inline = model
+            .value_nodes
+            .iter()
+            .map(|node| node.type_name.clone())
+            .chain(
+                model
+                    .operator_nodes
+                    .iter()
+                    .chain(model.keyword_nodes.iter())
+                    .map(|node| node.type_name.clone()),
+            )
+            .chain(std::iter::once(ANY_NODE.to_string()))
+            .collect();
+        let fallible = if fallible {
+            model
+                .root_node
+                .iter()
+                .chain(model.compound_nodes.iter())
+                .map(|node| node.type_name.clone())
+                .chain(
+                    model
+                        .super_type_nodes
+                        .iter()
+                        .chain(model.choice_nodes.iter())
+                        .map(|node| node.type_name.clone()),
+                )
+                .collect()
+        } else {
+            Default::default()
+        };
+        Self {
+            names,
+            inline,
+            fallible,
+        }
+    }
+
+    fn name(&self, cst_name: &str) -> String {
+        self.names
+            .get(cst_name)
+            .cloned()
+            .unwrap_or_else(|| cst_name.to_string())
+    }
+
+    ///
+    /// Values, tokens, operators and untyped nodes are held inline, all other types are boxed when
+    /// they are not already held within a collection.
+    ///
+    fn is_boxed(&self, cst_name: &str) -> bool {
+        !self.inline.contains(cst_name)
+    }
+
+    ///
+    /// The expression folding `value`, a reference to the wrapper type `cst_name`, into its AST
+    /// type with the `Fold` method for that type; in the fallible case this is a `Result` unless
+    /// `propagate` is set, in which case the error is returned with `?`.
+    ///
+    fn convert(&self, cst_name: &str, value: &str, propagate: bool) -> String {
+        let fold = format!("folder.fold_{}({value})", method_name(cst_name));
+        if self.fallible.contains(cst_name) && propagate {
+            format!("{fold}?")
+        } else {
+            fold
+        }
+    }
+
+    ///
+    /// The type of the AST member holding the value of the wrapper accessor `method_name`, whose
+    /// item is the wrapper type `item`, and the expression lowering it from `node`.
+    ///
+    fn member(
+        &self,
+        item: &str,
+        method_name: &str,
+        cardinality: Cardinality,
+        fallible: bool,
+    ) -> (String, String) {
+        let item_fallible = self.fallible.contains(item);
+        let boxed = self.is_boxed(item);
+        let call = format!("node.{method_name}()");
+        match cardinality {
+            Cardinality::Required => {
+                let access = if fallible { format!("{call}?") } else { call };
+                let value = self.convert(item, &format!("&{access}"), true);
+                if boxed {
+                    (
+                        format!("Box<{}>", self.name(item)),
+                        format!("Box::new({value})"),
+                    )
+                } else {
+                    (self.name(item), value)
+                }
+            }
+            Cardinality::Optional => {
+                let boxed_value = |value: String| {
+                    if boxed {
+                        format!("Box::new({value})")
+                    } else {
+                        value
+                    }
+                };
+                let lowering = if item_fallible {
+                    format!(
+                        "{call}?.map(|child| {}{}).transpose()?",
+                        self.convert(item, "&child", false),
+                        if boxed { ".map(Box::new)" } else { "" }
+                    )
+                } else if fallible {
+                    format!(
+                        "{call}?.map(|child| {})",
+                        boxed_value(self.convert(item, "&child", false))
+                    )
+                } else {
+                    format!(
+                        "{call}.map(|child| {})",
+                        boxed_value(self.convert(item, "&child", false))
+                    )
+                };
+                let item_type = if boxed {
+                    format!("Box<{}>", self.name(item))
+                } else {
+                    self.name(item)
+                };
+                (format!("Option<{item_type}>"), lowering)
+            }
+            Cardinality::Multiple => {
+                let lowering = if item_fallible {
+                    format!(
+                        "{call}.map(|child| child.and_then(|child| {})).collect::<Result<_, _>>()?",
+                        self.convert(item, "&child", false)
+                    )
+                } else if fallible {
+                    format!(
+                        "{call}.map(|child| child.map(|child| {})).collect::<Result<_, _>>()?",
+                        self.convert(item, "&child", false)
+                    )
+                } else {
+                    format!(
+                        "{call}.map(|child| {}).collect()",
+                        self.convert(item, "&child", false)
+                    )
+                };
+                (format!("Vec<{}>", self.name(item)), lowering)
+            }
+        }
+    }
+
+    fn ast_struct(
+        &self,
+        node: &CompoundNode,
+        cst_generics: &str,
+        fallible: bool,
+        grammar: &GrammarFile,
+    ) -> AstStruct {
+        let mut fields = node
+            .accessors
+            .iter()
+            .map(|accessor| {
+                let (field_type, lowering) = self.member(
+                    &accessor.item_name,
+                    &accessor.method_name,
+                    accessor.cardinality,
+                    fallible,
+                );
+                AstField {
+                    name: field_ident(&accessor.field_name),
+                    field_type,
+                    lowering,
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(children) = &node.children {
+            let (field_type, lowering) = self.member(
+                &children.item_name,
+                &children.method_name,
+                children.cardinality,
+                fallible,
+            );
+            let name = if fields
+                .iter()
+                .any(|field| field.name == children.method_name)
+            {
+                format!("unnamed_{}", children.method_name)
+            } else {
+                children.method_name.clone()
+            };
+            fields.push(AstField {
+                name,
+                field_type,
+                lowering,
+            });
+        }
+        let children = node
+            .children
+            .as_ref()
+            .zip(fields.last())
+            .map(|(children, field)| (field.name.clone(), children.cardinality));
+        for keyword in &node.keywords {
+            let (field_type, lowering) = match &keyword.variant_type {
+                Some(variant_type) => (
+                    format!("Option<{}>", self.name(variant_type)),
+                    format!(
+                        "node.{}().map(|variant| {})",
+                        keyword.method_name,
+                        self.convert(variant_type, "&variant", false)
+                    ),
+                ),
+                None => (
+                    "bool".to_string(),
+                    format!("node.{}()", keyword.method_name),
+                ),
+            };
+            let name = if fields.iter().any(|field| field.name == keyword.method_name) {
+                format!("keyword_{}", keyword.method_name)
+            } else {
+                keyword.method_name.clone()
+            };
+            fields.push(AstField {
+                name,
+                field_type,
+                lowering,
+            });
+        }
+        let leaf = node.accessors.is_empty() && node.children.is_none();
+        if leaf {
+            // a node matching a pattern, or a choice of keywords, has only its text.
+            fields.push(AstField {
+                name: LEAF_TEXT.to_string(),
+                field_type: "String".to_string(),
+                lowering: "wrapper::Spanned::text(node).to_string()".to_string(),
+            });
+        }
+        let mut members = UnparseMembers::new(
+            grammar,
+            children
+                .as_ref()
+                .map(|(name, cardinality)| (name.as_str(), *cardinality)),
+        );
+        for (accessor, field) in node.accessors.iter().zip(&fields) {
+            members = members.with_field(&accessor.field_name, &field.name, accessor.cardinality);
+        }
+        let keyword_fields = &fields[fields.len() - node.keywords.len() - usize::from(leaf)..];
+        for (keyword, field) in node.keywords.iter().zip(keyword_fields) {
+            members =
+                members.with_keyword(&keyword.kinds, &field.name, keyword.variant_type.is_some());
+        }
+        if leaf {
+            members = members.with_text(LEAF_TEXT);
+        }
+        let unparse = grammar
+            .visible_rules(&node.kind)
+            .first()
+            .map(|rule| members.statements(rule, 2))
+            .unwrap_or_default();
+        AstStruct {
+            kind: node.kind.clone(),
+            type_name: self.name(&node.type_name),
+            cst_type: format!("{}{cst_generics}", node.type_name),
+            method_suffix: node.method_suffix.clone(),
+            uses_folder: fields
+                .iter()
+                .any(|field| field.lowering.contains("folder.")),
+            fields,
+            unparse,
+        }
+    }
+
+    fn ast_operator(&self, node: &OperatorNode) -> AstOperator {
+        AstOperator {
+            doc: node.doc.clone(),
+            type_name: self.name(&node.type_name),
+            cst_name: node.type_name.clone(),
+            method_suffix: node.method_suffix.clone(),
+            variants: node.variants.clone(),
+        }
+    }
+
+    fn ast_enum(&self, node: &EnumNode, fallible: bool) -> AstEnum {
+        AstEnum {
+            doc: node.doc.clone(),
+            type_name: self.name(&node.type_name),
+            cst_name: node.type_name.clone(),
+            cst_type: format!(
+                "{}{}",
+                node.type_name,
+                if node.generics.is_empty() {
+                    ""
+                } else {
+                    "<'_, '_>"
+                }
+            ),
+            method_suffix: node.method_suffix.clone(),
+            fallible,
+            variants: node
+                .variants
+                .iter()
+                .map(|variant| AstVariant {
+                    name: variant.name.clone(),
+                    type_name: self.name(&variant.item_name),
+                    lowering: self.convert(&variant.item_name, "child", true),
+                })
+                .collect(),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::{AstFile, render_with};
+
+    #[test]
+    fn test_owned_ast_types() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert!(rendered.contains("pub struct Module {"));
+        assert!(rendered.contains("    pub name: Identifier,"));
+        assert!(rendered.contains("    pub base: Option<Box<Iri>>,"));
+        assert!(rendered.contains("pub enum Definition {"));
+        assert!(rendered.contains("pub struct Identifier(pub String);"));
+        assert!(rendered.contains("pub struct StringNode {"));
+        assert!(rendered.contains("    pub r#type: "));
+        assert!(rendered.contains("pub enum DigitRestrictionFacetFacet {\n    FractionDigits,"));
+        assert!(rendered.contains("    pub facet: DigitRestrictionFacetFacet,"));
+        assert!(!rendered.contains("'t"));
+        assert!(!rendered.contains("serde::Serialize"));
+        assert!(rendered.contains("pub struct Iri {\n    pub text: String,\n}"));
+        assert!(rendered.contains(
+            "pub struct BuiltinTypes {\n    pub variant: Option<BuiltinTypesVariant>,\n    pub text: String,\n}"
+        ));
+        assert!(rendered.contains("pub enum BuiltinTypesVariant {"));
+    }
+
+    #[test]
+    fn test_lowering() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert!(rendered.contains("impl From<&wrapper::ModuleNode<'_>> for Module {"));
+        assert!(rendered.contains(
+            "    fn from(node: &wrapper::ModuleNode<'_>) -> Self {\n        Lowering.fold_module_node(node)\n"
+        ));
+        assert!(
+            rendered.contains("        name: folder.fold_identifier_value(&node.field_name()),")
+        );
+
+        let rendered = render_with(&AstFile, |arguments| {
+            arguments.with_fallible(true).with_serde(true)
+        });
+        assert!(rendered.contains("impl TryFrom<&wrapper::ModuleNode<'_>> for Module {"));
+        assert!(
+            rendered.contains("        name: folder.fold_identifier_value(&node.field_name()?),")
+        );
+        assert!(
+            rendered.contains(
+                "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]"
+            )
+        );
+
+        let rendered = render_with(&AstFile, |arguments| arguments.with_shared_tree(true));
+        assert!(rendered.contains("impl From<&wrapper::ModuleNode> for Module {"));
+    }
+
+    #[test]
+    fn test_fold_trait() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert!(rendered.contains("pub trait Fold {"));
+        assert!(rendered.contains(
+            "    fn fold_module_node(&mut self, node: &wrapper::ModuleNode<'_>) -> Module {\n        fold_module_node(self, node)\n"
+        ));
+        assert!(rendered.contains(
+            "pub fn fold_entity_def_node<F>(folder: &mut F, node: &wrapper::EntityDefNode<'_, '_>) -> EntityDef\nwhere\n    F: Fold + ?Sized,"
+        ));
+        assert!(rendered.contains(
+            "    fn fold_identifier_value(&mut self, node: &wrapper::IdentifierValue) -> Identifier {\n        Identifier::from(node)\n"
+        ));
+        assert!(rendered.contains("pub fn fold_iri_node<F>(_folder: &mut F,"));
+        assert!(rendered.contains("impl Fold for Lowering {}"));
+
+        let rendered = render_with(&AstFile, |arguments| arguments.with_fallible(true));
+        assert!(rendered.contains(
+            "    fn fold_module_node(&mut self, node: &wrapper::ModuleNode<'_>) -> Result<Module, wrapper::SdmlError> {"
+        ));
+        assert!(rendered.contains(
+            "        body: Box::new(folder.fold_module_body_node(&node.field_body()?)?),"
+        ));
+    }
+
+    #[test]
+    fn test_unparse() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert!(rendered.contains("pub trait Unparse {"));
+        assert!(rendered.contains("impl Unparse for Module {"));
+        assert!(
+            rendered.contains(
+                "        printer.token(\"module\")?;\n        self.name.unparse(printer)?;"
+            )
+        );
+        assert!(rendered.contains(
+            "            if let Some(value) = remaining_children.next_if(|value| matches!(value.kind(), \"member\")) {"
+        ));
+        assert!(rendered.contains("        printer.token(&self.text)?;"));
+        assert!(rendered.contains("        printer.immediate_token(&self.0)"));
+        assert!(
+            rendered
+                .contains("        printer.immediate_token(\":\")?;\n        printer.attach();")
+        );
+        assert!(rendered.contains("impl fmt::Display for Module {"));
+        assert!(rendered.contains("            Self::EntityDef(child) => child.unparse(printer),"));
+    }
+}
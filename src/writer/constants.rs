@@ -1,14 +1,35 @@
 /*!
-One-line description.
+Generates string constants for all the node kinds and field names in `node-types.json`.
+
+Where [`Arguments::with_node_kinds`] is set the file also contains a `NodeKind` enum, with one
+variant for each named, super-type, and anonymous node kind, so that a match on node kinds is
+checked for exhaustiveness when the grammar changes. Variants are named for their kind, as for
+the generated wrapper types, and an anonymous kind whose name would clash with a named kind is
+given a `Token` suffix.
+
+Where [`Arguments::with_field_ids`] is set the file also contains a `FieldName` enum, with one
+variant for each field name. As `tree-sitter` only assigns field ids when a language is loaded
+the enum's `id` method looks up the ids of all fields in the first language it is given and
+caches them, so that the generated wrapper can find fields with `Node::child_by_field_id`.
+
+Where [`Arguments::with_kind_ids`] is set the file also contains a table of the numeric id of
+every node kind, looked up with `Language::id_for_node_kind` in either the language passed to
+`init_kind_ids` or the language of the first node tested, and an `is_<kind>` predicate for each
+named kind that compares `Node::kind_id` with this table rather than comparing strings. With both
+options set `NodeKind::from_node` also uses this table.
 
  */
 
 use crate::{
     error::Error,
     reader::NodeTypesFile,
-    writer::{Arguments, Output},
+    writer::{
+        Arguments, Output,
+        naming::{method_name, token_name, type_name},
+    },
 };
-use std::io::Write;
+use serde::Serialize;
+use std::{collections::BTreeSet, io::Write};
 use tera::Tera;
 
 // ------------------------------------------------------------------------------------------------
@@ -18,6 +39,29 @@ use tera::Tera;
 #[derive(Clone, Debug, PartialEq)]
 pub struct ConstantsFile;
 
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+struct NodeKind {
+    name: String,
+    kind: String,
+    literal: String,
+    named: bool,
+    super_type: bool,
+    /// `false` where a named kind has the same text, which `FromStr` prefers.
+    from_str: bool,
+    /// The `is_<kind>` predicate for named kinds.
+    predicate: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct FieldName {
+    name: String,
+    field: String,
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations
 // ------------------------------------------------------------------------------------------------
@@ -42,11 +86,34 @@ impl Output for ConstantsFile {
             "node_names",
             &arguments.input_file.regular_node_type_names(),
         );
+        context.insert(
+            "named_terminal_names",
+            &arguments.input_file.named_terminal_node_type_names(),
+        );
         context.insert("field_names", &arguments.input_file.field_names());
         context.insert(
             "terminal_names",
             &arguments.input_file.terminal_node_type_names(),
         );
+        if arguments.node_kinds() || arguments.kind_ids() {
+            context.insert("node_kinds", &node_kinds(&arguments.input_file));
+            context.insert("node_kind_enum", &arguments.node_kinds());
+            context.insert("kind_ids", &arguments.kind_ids());
+        }
+        if arguments.field_ids() {
+            context.insert(
+                "field_ids",
+                &arguments
+                    .input_file
+                    .field_names()
+                    .into_iter()
+                    .map(|field| FieldName {
+                        name: type_name(field),
+                        field: field.clone(),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
 
         let rendered = tera
             .render(&format!("constants.{}", arguments.for_language), &context)
@@ -56,3 +123,124 @@ impl Output for ConstantsFile {
         Ok(())
     }
 }
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The variants of the `NodeKind` enum, which are also the entries in the kind id table; named and
+/// super-type kinds are named first so that they keep the plain type name where an anonymous kind
+/// would otherwise clash with them.
+///
+fn node_kinds(node_types: &NodeTypesFile) -> Vec<NodeKind> {
+    let mut names = BTreeSet::new();
+    let mut kinds = BTreeSet::new();
+    let mut node_kinds = Vec::new();
+
+    for named in [true, false] {
+        for defn in node_types
+            .definitions()
+            .filter(|defn| defn.node_type().is_named() == named)
+        {
+            let kind = defn.node_type_name();
+            let mut name = if named {
+                type_name(kind)
+            } else {
+                token_name(kind)
+            };
+            while names.contains(&name) {
+                name = format!("{name}Token");
+            }
+            names.insert(name.clone());
+            let super_type = defn.kind().is_super_type();
+            node_kinds.push(NodeKind {
+                predicate: (named && !super_type)
+                    .then(|| format!("is_{}", method_name(type_name(kind)))),
+                name,
+                kind: kind.clone(),
+                literal: format!("{kind:?}"),
+                named,
+                super_type,
+                from_str: kinds.insert(kind.clone()),
+            });
+        }
+    }
+    node_kinds
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::{ConstantsFile, render_with};
+
+    #[test]
+    fn test_node_kinds_are_optional() {
+        let rendered = render_with(&ConstantsFile, |arguments| arguments);
+        assert!(rendered.contains("pub const NODE_TYPE_MODULE: &str = \"module\";"));
+        assert!(!rendered.contains("pub enum NodeKind"));
+    }
+
+    #[test]
+    fn test_fixture_is_current() {
+        let rendered = render_with(&ConstantsFile, |arguments| arguments);
+        let fixture = std::fs::read_to_string("./tests/nodes.rs").unwrap();
+        assert_eq!(rendered, fixture.replace("\r\n", "\n"));
+        let rendered = render_with(&ConstantsFile, |arguments| {
+            arguments
+                .with_node_kinds(true)
+                .with_field_ids(true)
+                .with_kind_ids(true)
+        });
+        assert!(!rendered.contains("\n\n\n"));
+    }
+
+    #[test]
+    fn test_node_kind_enum() {
+        let rendered = render_with(&ConstantsFile, |arguments| arguments.with_node_kinds(true));
+        assert!(rendered.contains("pub enum NodeKind {"));
+        assert!(rendered.contains("    Definition,"));
+        assert!(rendered.contains("    Module,"));
+        assert!(rendered.contains("    ModuleToken,"));
+        assert!(rendered.contains("            Self::ModuleToken => \"module\","));
+        assert!(rendered.contains("            (\"module\", false) => Some(Self::ModuleToken),"));
+        assert!(rendered.contains("impl std::str::FromStr for NodeKind {"));
+    }
+
+    #[test]
+    fn test_kind_id_predicates() {
+        let rendered = render_with(&ConstantsFile, |arguments| arguments.with_kind_ids(true));
+        assert!(!rendered.contains("pub enum NodeKind"));
+        assert!(rendered.contains("static KIND_IDS: std::sync::OnceLock<KindIds>"));
+        assert!(
+            rendered.contains("pub fn init_kind_ids(language: &tree_sitter::Language) -> bool {")
+        );
+        assert!(rendered.contains("pub fn is_module_body(node: &tree_sitter::Node<'_>) -> bool {"));
+        assert!(!rendered.contains("pub fn is_definition("));
+    }
+
+    #[test]
+    fn test_node_kind_from_kind_id() {
+        let rendered = render_with(&ConstantsFile, |arguments| {
+            arguments.with_node_kinds(true).with_kind_ids(true)
+        });
+        assert!(rendered.contains("    kinds: Vec<Option<NodeKind>>,"));
+        assert!(!rendered.contains("match (node.kind(), node.is_named()) {"));
+    }
+
+    #[test]
+    fn test_field_name_enum() {
+        let rendered = render_with(&ConstantsFile, |arguments| arguments.with_field_ids(true));
+        assert!(rendered.contains("pub const FIELD_BODY: &str = \"body\";"));
+        assert!(rendered.contains("pub enum FieldName {"));
+        assert!(rendered.contains("    Body,"));
+        assert!(rendered.contains("            Self::Body => \"body\","));
+        assert!(rendered.contains(
+            "        FIELD_IDS.get_or_init(|| Self::ALL.map(|field| language.field_id_for_name(field.as_str())))"
+        ));
+        assert!(rendered.contains("impl std::str::FromStr for FieldName {"));
+    }
+}
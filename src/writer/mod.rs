@@ -1,10 +1,26 @@
 /*!
-One-line description.
+Provides the [`Output`] trait, implemented by each of the generated files, and the [`Arguments`]
+that configure them.
+
+Each writer reads an [`InputFile`] and renders its output for a target [`ForLanguage`] using the
+Tera templates in the `templates` directory:
+
+- [`ConstantsFile`] writes string constants for the node kinds and field names in
+  `node-types.json`, optionally with `NodeKind` and `FieldName` enums and a kind id table.
+- [`WrapperFile`] writes typed wrappers around the tree-sitter CST, reading both `grammar.json`
+  and `node-types.json`.
+- [`AstFile`] writes an owned AST, with a lowering from the typed wrappers and an unparser back to
+  source text; it must be given the same arguments as the wrapper it lowers from.
+
+[`Arguments`] is built with [`Arguments::new`] and a `with_` method for each option; options that
+do not apply to a writer are ignored by it. [`ValueMode`] selects whether the wrapper's value nodes
+copy or borrow their text.
 
  */
 
 use crate::{error::Error, reader::InputFile};
 use std::{
+    collections::BTreeMap,
     fmt::{Display, Formatter, Result as FmtResult},
     fs::File,
     io::{BufWriter, Write, stdout},
@@ -81,6 +97,15 @@ pub struct Arguments<F: InputFile> {
     input_file: F,
     for_language: ForLanguage,
     output_directory: Option<PathBuf>,
+    share_choices: bool,
+    fallible: bool,
+    value_mode: ValueMode,
+    value_types: BTreeMap<String, String>,
+    serde: bool,
+    node_kinds: bool,
+    field_ids: bool,
+    kind_ids: bool,
+    shared_tree: bool,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -89,6 +114,18 @@ pub enum ForLanguage {
     Rust,
 }
 
+///
+/// Determines how generated value nodes hold the text of their terminal.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValueMode {
+    /// Value nodes copy the node's text into an owned `String` when constructed.
+    #[default]
+    Owned,
+    /// Value nodes keep the `Node` and borrow their text from the source buffer.
+    Borrowed,
+}
+
 // ------------------------------------------------------------------------------------------------
 // Implementations ❱ Arguments
 // ------------------------------------------------------------------------------------------------
@@ -103,8 +140,150 @@ impl<F: InputFile> Arguments<F> {
             input_file,
             for_language,
             output_directory,
+            share_choices: false,
+            fallible: false,
+            value_mode: Default::default(),
+            value_types: Default::default(),
+            serde: false,
+            node_kinds: false,
+            field_ids: false,
+            kind_ids: false,
+            shared_tree: false,
+        }
+    }
+
+    ///
+    /// When set, fields and children that accept an identical set of node types share a single
+    /// generated choice type rather than each having their own.
+    ///
+    pub fn with_share_choices(self, share_choices: bool) -> Self {
+        Self {
+            share_choices,
+            ..self
+        }
+    }
+
+    pub fn share_choices(&self) -> bool {
+        self.share_choices
+    }
+
+    ///
+    /// When set, generated accessors return a `Result` rather than panicking when the tree does
+    /// not match the grammar, for example where it contains `ERROR` or `MISSING` nodes.
+    ///
+    pub fn with_fallible(self, fallible: bool) -> Self {
+        Self { fallible, ..self }
+    }
+
+    pub fn fallible(&self) -> bool {
+        self.fallible
+    }
+
+    pub fn with_value_mode(self, value_mode: ValueMode) -> Self {
+        Self { value_mode, ..self }
+    }
+
+    pub fn value_mode(&self) -> ValueMode {
+        self.value_mode
+    }
+
+    ///
+    /// Map the named terminal node `kind` to a Rust type implementing `FromStr`, such as
+    /// `integer` to `i64`; the generated value node will then provide a typed `value()` method.
+    ///
+    pub fn with_value_type<K, T>(mut self, kind: K, type_name: T) -> Self
+    where
+        K: Into<String>,
+        T: Into<String>,
+    {
+        self.value_types.insert(kind.into(), type_name.into());
+        self
+    }
+
+    pub fn with_value_types<I, K, T>(self, value_types: I) -> Self
+    where
+        I: IntoIterator<Item = (K, T)>,
+        K: Into<String>,
+        T: Into<String>,
+    {
+        value_types
+            .into_iter()
+            .fold(self, |arguments, (kind, type_name)| {
+                arguments.with_value_type(kind, type_name)
+            })
+    }
+
+    pub fn value_types(&self) -> &BTreeMap<String, String> {
+        &self.value_types
+    }
+
+    pub fn value_type(&self, kind: &str) -> Option<&String> {
+        self.value_types.get(kind)
+    }
+
+    ///
+    /// When set, generated AST types also derive `serde::Serialize` and `serde::Deserialize`.
+    ///
+    pub fn with_serde(self, serde: bool) -> Self {
+        Self { serde, ..self }
+    }
+
+    pub fn serde(&self) -> bool {
+        self.serde
+    }
+
+    ///
+    /// When set, the constants file also contains a `NodeKind` enum with a variant for every
+    /// named, super-type, and anonymous node kind.
+    ///
+    pub fn with_node_kinds(self, node_kinds: bool) -> Self {
+        Self { node_kinds, ..self }
+    }
+
+    pub fn node_kinds(&self) -> bool {
+        self.node_kinds
+    }
+
+    ///
+    /// When set, the constants file also contains a `FieldName` enum which looks up, and caches,
+    /// the `FieldId` of each field, and generated wrapper accessors find fields by id rather than
+    /// by name.
+    ///
+    pub fn with_field_ids(self, field_ids: bool) -> Self {
+        Self { field_ids, ..self }
+    }
+
+    pub fn field_ids(&self) -> bool {
+        self.field_ids
+    }
+
+    ///
+    /// When set, the constants file also contains a table of node kind ids, looked up at runtime
+    /// from a `Language`, and an `is_<kind>` predicate for each named kind that compares ids.
+    ///
+    pub fn with_kind_ids(self, kind_ids: bool) -> Self {
+        Self { kind_ids, ..self }
+    }
+
+    pub fn kind_ids(&self) -> bool {
+        self.kind_ids
+    }
+
+    ///
+    /// When set, generated wrappers hold a reference-counted tree and source, and the position of
+    /// their node within it, rather than borrowing them; wrappers are then `Send`, `Sync`, and
+    /// `'static`.
+    ///
+    pub fn with_shared_tree(self, shared_tree: bool) -> Self {
+        Self {
+            shared_tree,
+            ..self
         }
     }
+
+    pub fn shared_tree(&self) -> bool {
+        self.shared_tree
+    }
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -150,12 +329,82 @@ impl ForLanguage {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+// Implementations ❱ ValueMode
+// ------------------------------------------------------------------------------------------------
+
+impl Display for ValueMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Owned => "owned",
+                Self::Borrowed => "borrowed",
+            }
+        )
+    }
+}
+
+impl FromStr for ValueMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owned" => Ok(Self::Owned),
+            "borrowed" => Ok(Self::Borrowed),
+            _ => Err(Error::Unknown {
+                message: format!("could not parse `{s}` into `ValueMode`"),
+            }),
+        }
+    }
+}
+
+impl ValueMode {
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Borrowed)
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Modules
 // ------------------------------------------------------------------------------------------------
 
+pub mod ast;
+pub use ast::AstFile;
+
 pub mod constants;
 pub use constants::ConstantsFile;
 
+pub(crate) mod naming;
+
+pub(crate) mod unparse;
+
 pub mod wrapper;
 pub use wrapper::WrapperFile;
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Render `output` for the SDML grammar in `./tests`, with arguments adjusted by `configure`;
+/// shared by the unit tests of each writer.
+///
+#[cfg(test)]
+pub(crate) fn render_with<O, F>(output: &O, configure: F) -> String
+where
+    O: Output,
+    F: FnOnce(Arguments<O::InputFile>) -> Arguments<O::InputFile>,
+{
+    let input = O::InputFile::from_file(format!("./tests/{}", O::InputFile::DEFAULT_FILE_NAME))
+        .expect("could not read the test grammar");
+    let mut buffer = Vec::new();
+    output
+        .write(
+            configure(Arguments::new(input, ForLanguage::Rust, None)),
+            &mut buffer,
+        )
+        .expect("could not render the test grammar");
+    String::from_utf8(buffer).expect("rendered output was not UTF-8")
+}
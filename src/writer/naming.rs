@@ -0,0 +1,211 @@
+/*!
+Helper functions to turn tree-sitter node, field, and token names into identifiers.
+
+ */
+
+// ------------------------------------------------------------------------------------------------
+// Public Functions
+// ------------------------------------------------------------------------------------------------
+
+///
+/// Convert a tree-sitter name such as `module_body` into an upper camel-case type name such as
+/// `ModuleBody`. Any character that is not alphanumeric is treated as a word separator.
+///
+pub(crate) fn type_name<S: AsRef<str>>(name: S) -> String {
+    name.as_ref()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+///
+/// Convert the text of an anonymous token into a type or variant name. Alphanumeric words are
+/// converted as in [`type_name`], punctuation is spelled out (`->` becomes `DashGreaterThan`),
+/// and any other character is written as its Unicode code point (`∧` becomes `U2227`).
+///
+pub(crate) fn token_name<S: AsRef<str>>(token: S) -> String {
+    let mut name = String::new();
+    let mut word = String::new();
+    for c in token.as_ref().chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c);
+            continue;
+        }
+        name.push_str(&type_name(&word));
+        word.clear();
+        match punctuation_name(c) {
+            Some(punctuation) => name.push_str(punctuation),
+            None if c == '_' || c.is_whitespace() => {}
+            None => name.push_str(&format!("U{:04X}", c as u32)),
+        }
+    }
+    name.push_str(&type_name(&word));
+    match name.chars().next() {
+        None => "Token".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("Token{name}"),
+        Some(_) => name,
+    }
+}
+
+///
+/// Returns `true` if [`token_name`] spells out every character of `token`, rather than writing
+/// any as a Unicode code point.
+///
+pub(crate) fn is_spelled_out<S: AsRef<str>>(token: S) -> bool {
+    token.as_ref().chars().all(|c| {
+        c.is_ascii_alphanumeric() || c == '_' || c.is_whitespace() || punctuation_name(c).is_some()
+    })
+}
+
+///
+/// Convert a tree-sitter name into the suffix used for the corresponding constant in the
+/// generated constants file; this must match the `upper` filter used by the constants template.
+///
+pub(crate) fn constant_name<S: AsRef<str>>(name: S) -> String {
+    name.as_ref().to_uppercase()
+}
+
+///
+/// Convert an upper camel-case type name such as `ModuleBodyNode` into a snake-case method name
+/// such as `module_body_node`.
+///
+pub(crate) fn method_name<S: AsRef<str>>(type_name: S) -> String {
+    let mut name = String::new();
+    let mut previous_lower = false;
+    for c in type_name.as_ref().chars() {
+        if c.is_ascii_uppercase() {
+            if previous_lower {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+            previous_lower = false;
+        } else {
+            name.push(c);
+            previous_lower = c.is_ascii_alphanumeric();
+        }
+    }
+    name
+}
+
+///
+/// Convert a tree-sitter field name into a Rust identifier; keywords are written as raw
+/// identifiers (`type` becomes `r#type`), except those that cannot be raw which are suffixed.
+///
+pub(crate) fn field_ident<S: AsRef<str>>(name: S) -> String {
+    let name = name.as_ref();
+    if matches!(name, "crate" | "self" | "Self" | "super") {
+        format!("{name}_")
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+const fn punctuation_name(c: char) -> Option<&'static str> {
+    Some(match c {
+        '!' => "Bang",
+        '"' => "DoubleQuote",
+        '#' => "Hash",
+        '$' => "Dollar",
+        '%' => "Percent",
+        '&' => "Ampersand",
+        '\'' => "Quote",
+        '(' => "LeftParen",
+        ')' => "RightParen",
+        '*' => "Star",
+        '+' => "Plus",
+        ',' => "Comma",
+        '-' => "Dash",
+        '.' => "Dot",
+        '/' => "Slash",
+        ':' => "Colon",
+        ';' => "Semicolon",
+        '<' => "LessThan",
+        '=' => "Equals",
+        '>' => "GreaterThan",
+        '?' => "Question",
+        '@' => "At",
+        '[' => "LeftBracket",
+        '\\' => "Backslash",
+        ']' => "RightBracket",
+        '^' => "Caret",
+        '`' => "Backtick",
+        '{' => "LeftBrace",
+        '|' => "Bar",
+        '}' => "RightBrace",
+        '~' => "Tilde",
+        _ => return None,
+    })
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("module"), "Module");
+        assert_eq!(type_name("module_body"), "ModuleBody");
+        assert_eq!(type_name("_hidden_rule"), "HiddenRule");
+        assert_eq!(type_name("fractionDigits"), "FractionDigits");
+    }
+
+    #[test]
+    fn test_token_name() {
+        assert_eq!(token_name("fractionDigits"), "FractionDigits");
+        assert_eq!(token_name("["), "LeftBracket");
+        assert_eq!(token_name("->"), "DashGreaterThan");
+        assert_eq!(token_name("exists!"), "ExistsBang");
+        assert_eq!(token_name("∧"), "U2227");
+    }
+
+    #[test]
+    fn test_is_spelled_out() {
+        assert!(is_spelled_out("->"));
+        assert!(is_spelled_out("exists!"));
+        assert!(!is_spelled_out("∉"));
+    }
+
+    #[test]
+    fn test_method_name() {
+        assert_eq!(method_name("ModuleBodyNode"), "module_body_node");
+        assert_eq!(method_name("TokenValue"), "token_value");
+        assert_eq!(method_name("LeftBracketU2227"), "left_bracket_u2227");
+    }
+
+    #[test]
+    fn test_field_ident() {
+        assert_eq!(field_ident("name"), "name");
+        assert_eq!(field_ident("type"), "r#type");
+        assert_eq!(field_ident("self"), "self_");
+    }
+
+    #[test]
+    fn test_constant_name() {
+        assert_eq!(constant_name("module_body"), "MODULE_BODY");
+    }
+}
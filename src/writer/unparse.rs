@@ -0,0 +1,763 @@
+/*!
+Translates the grammar rule for a node into the Rust statements that write the node back out as
+source text, used by the AST generator to implement `Unparse` for each AST struct.
+
+The statements walk the rule in order, writing each literal as a token and each `FIELD` as the
+value of the corresponding AST member; any other named symbol is taken from the node's un-named
+children, but only where the next child is of one of the kinds the symbol may produce, so that
+children are matched to their place in the rule. A `CHOICE` of literals is written as the spelling
+held in the node's keyword member, and an optional literal only where its keyword member is set.
+Any other `CHOICE` picks the first alternative whose fields or children are present, falling back
+to a `BLANK` alternative or else the first alternative with nothing to test; `REPEAT` and
+`REPEAT1` continue while the fields or children within them have values remaining.
+
+A node with neither fields nor children, such as one matching a pattern, is written as the text
+it holds. A `token.immediate` is written with no whitespace before it and, where it is followed by
+more of the same sequence, after it, as is a literal wrapped in `token` that is not a word, such as
+a sigil. Patterns within a node that has fields or children cannot be reproduced and are skipped.
+
+ */
+
+use crate::{
+    reader::{GrammarFile, grammar::GrammarRule},
+    writer::wrapper::Cardinality,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+// ------------------------------------------------------------------------------------------------
+// Public Types
+// ------------------------------------------------------------------------------------------------
+
+///
+/// The AST members of a node that the statements for its grammar rule may write: each field, by
+/// field name, with its member name and cardinality, the member holding un-named children with
+/// its cardinality, the members holding keywords, and the member holding the text of a node with
+/// neither fields nor children.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct UnparseMembers<'a> {
+    grammar: &'a GrammarFile,
+    fields: BTreeMap<&'a str, (&'a str, Cardinality)>,
+    children: Option<(&'a str, Cardinality)>,
+    keywords: Vec<KeywordMember<'a>>,
+    text: Option<&'a str>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Types
+// ------------------------------------------------------------------------------------------------
+
+const INDENT: &str = "    ";
+
+/// A member holding either which spelling of a keyword is present, or whether an optional
+/// keyword is present.
+#[derive(Clone, Debug)]
+struct KeywordMember<'a> {
+    /// The Rust literal for each spelling.
+    literals: &'a [String],
+    member: &'a str,
+    /// `true` where the member is an `Option` of the spellings, `false` where it is a `bool`.
+    variant: bool,
+}
+
+/// Whether the members within a rule have values to write.
+#[derive(Clone, Debug, PartialEq)]
+enum Presence {
+    /// The rule contains a required field.
+    Always,
+    /// The rule contains no fields or children that can be tested.
+    Unknown,
+    /// The rule contains optional or repeated members, any of which may have a value.
+    When(Vec<Condition>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Condition {
+    expression: String,
+    /// The iterator over repeated members consumed when this condition holds, if any.
+    iterator: Option<String>,
+}
+
+struct Statements<'a> {
+    members: &'a UnparseMembers<'a>,
+    iterators: BTreeSet<String>,
+    /// The tests known to hold at the current statement, from enclosing `if` and `while`.
+    known: Vec<String>,
+    lines: Vec<String>,
+}
+
+// ------------------------------------------------------------------------------------------------
+// Implementations
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> UnparseMembers<'a> {
+    pub(crate) fn new(grammar: &'a GrammarFile, children: Option<(&'a str, Cardinality)>) -> Self {
+        Self {
+            grammar,
+            fields: Default::default(),
+            children,
+            keywords: Default::default(),
+            text: None,
+        }
+    }
+
+    pub(crate) fn with_field(
+        mut self,
+        field_name: &'a str,
+        member: &'a str,
+        cardinality: Cardinality,
+    ) -> Self {
+        self.fields.insert(field_name, (member, cardinality));
+        self
+    }
+
+    ///
+    /// Add the member holding a keyword with the spellings `literals`; an `Option` of which
+    /// spelling is present where `variant` is set, otherwise a `bool`.
+    ///
+    pub(crate) fn with_keyword(
+        mut self,
+        literals: &'a [String],
+        member: &'a str,
+        variant: bool,
+    ) -> Self {
+        self.keywords.push(KeywordMember {
+            literals,
+            member,
+            variant,
+        });
+        self
+    }
+
+    pub(crate) fn with_text(mut self, member: &'a str) -> Self {
+        self.text = Some(member);
+        self
+    }
+
+    ///
+    /// Returns the statements writing a node with these members according to `rule`, each line
+    /// indented by `indent` levels. Iterators over repeated members are declared first.
+    ///
+    pub(crate) fn statements(&self, rule: &GrammarRule, indent: usize) -> Vec<String> {
+        let prefix = INDENT.repeat(indent);
+        if let Some(text) = self.text {
+            return vec![format!(
+                "{prefix}printer.{}(&self.{text})?;",
+                token_method(is_immediate(rule))
+            )];
+        }
+        let mut statements = Statements {
+            members: self,
+            iterators: Default::default(),
+            known: Default::default(),
+            lines: Default::default(),
+        };
+        statements.rule(rule, indent);
+        statements
+            .iterators
+            .iter()
+            .map(|member| match self.children {
+                Some((children, Cardinality::Required)) if children == member => format!(
+                    "{prefix}let mut {} = std::iter::once(&self.{member}).peekable();",
+                    iterator_name(member)
+                ),
+                _ => format!(
+                    "{prefix}let mut {} = self.{member}.iter().peekable();",
+                    iterator_name(member)
+                ),
+            })
+            .chain(statements.lines)
+            .collect()
+    }
+
+    fn presence(&self, rule: &GrammarRule) -> Presence {
+        if let Some(keyword) = rule
+            .literal()
+            .and_then(|literal| self.keyword_flag(literal))
+        {
+            return Presence::When(vec![Condition {
+                expression: format!("self.{}", keyword.member),
+                iterator: None,
+            }]);
+        }
+        match rule {
+            GrammarRule::Field { name, content } => {
+                match self.fields.get(name.to_string().as_str()) {
+                    Some((_, Cardinality::Required)) => Presence::Always,
+                    Some((member, Cardinality::Optional)) => Presence::When(vec![Condition {
+                        expression: match self.kinds(content) {
+                            Some(kinds) => format!(
+                                "self.{member}.as_ref().is_some_and(|value| {})",
+                                kind_test(&kinds)
+                            ),
+                            None => format!("self.{member}.is_some()"),
+                        },
+                        iterator: None,
+                    }]),
+                    Some((member, Cardinality::Multiple)) => {
+                        Presence::When(vec![Condition::next(member, self.kinds(content))])
+                    }
+                    None => Presence::Unknown,
+                }
+            }
+            GrammarRule::Symbol { .. } | GrammarRule::Alias { named: true, .. } => {
+                match self.children {
+                    Some((member, _)) => {
+                        Presence::When(vec![Condition::next(member, self.kinds(rule))])
+                    }
+                    None => Presence::Unknown,
+                }
+            }
+            GrammarRule::Choice { members } => match self.keyword_choice(members) {
+                Some(keyword) if members.contains(&GrammarRule::Blank) => {
+                    Presence::When(vec![Condition {
+                        expression: format!("self.{}.is_some()", keyword.member),
+                        iterator: None,
+                    }])
+                }
+                Some(_) => Presence::Unknown,
+                None => members
+                    .iter()
+                    .map(|member| self.presence(member))
+                    .fold(Presence::Unknown, Presence::or),
+            },
+            GrammarRule::Alias { .. }
+            | GrammarRule::Token { .. }
+            | GrammarRule::ImmediateToken { .. } => Presence::Unknown,
+            _ => rule
+                .contents()
+                .into_iter()
+                .map(|content| self.presence(content))
+                .fold(Presence::Unknown, Presence::or),
+        }
+    }
+
+    ///
+    /// The kinds of node that `rule` may produce, with super-types and hidden rules replaced by
+    /// their content; `None` if it may produce a token matching a pattern.
+    ///
+    fn kinds(&self, rule: &GrammarRule) -> Option<BTreeSet<String>> {
+        let mut kinds = BTreeSet::new();
+        self.collect_kinds(rule, &mut kinds, &mut Vec::new())
+            .then_some(kinds)
+    }
+
+    fn collect_kinds(
+        &self,
+        rule: &GrammarRule,
+        kinds: &mut BTreeSet<String>,
+        stack: &mut Vec<String>,
+    ) -> bool {
+        if let Some(literal) = rule.literal() {
+            kinds.insert(literal.to_string());
+            return true;
+        }
+        match rule {
+            GrammarRule::Symbol { name } => {
+                let name = name.to_string();
+                let grammar = self.grammar;
+                let expand = grammar.is_super_type(&name)
+                    || grammar.is_hidden_rule(&name)
+                    || grammar.is_inlined_rule(&name);
+                match grammar.rule(&name).filter(|_| expand) {
+                    // a recursive reference adds no kinds that have not already been seen.
+                    Some(_) if stack.contains(&name) => true,
+                    Some(content) => {
+                        stack.push(name);
+                        let found = self.collect_kinds(content, kinds, stack);
+                        stack.pop();
+                        found
+                    }
+                    None => {
+                        kinds.insert(name);
+                        true
+                    }
+                }
+            }
+            GrammarRule::Alias { value, .. } => {
+                kinds.insert(value.to_string());
+                true
+            }
+            GrammarRule::Blank => true,
+            GrammarRule::Pattern { .. }
+            | GrammarRule::Token { .. }
+            | GrammarRule::ImmediateToken { .. } => false,
+            _ => rule
+                .contents()
+                .into_iter()
+                .all(|content| self.collect_kinds(content, kinds, stack)),
+        }
+    }
+
+    /// The member holding which spelling of the choice of literals `members` is present.
+    fn keyword_choice(&self, members: &[GrammarRule]) -> Option<&KeywordMember<'a>> {
+        let literals = members
+            .iter()
+            .filter(|member| **member != GrammarRule::Blank)
+            .map(|member| member.literal().map(|literal| format!("{literal:?}")))
+            .collect::<Option<BTreeSet<_>>>()?;
+        self.keywords.iter().find(|keyword| {
+            keyword.variant
+                && keyword.literals.iter().collect::<BTreeSet<_>>() == literals.iter().collect()
+        })
+    }
+
+    /// The member holding whether the optional keyword `literal` is present.
+    fn keyword_flag(&self, literal: &str) -> Option<&KeywordMember<'a>> {
+        let literal = format!("{literal:?}");
+        self.keywords
+            .iter()
+            .find(|keyword| !keyword.variant && keyword.literals == [literal.clone()])
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Presence {
+    fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Always, _) | (_, Self::Always) => Self::Always,
+            (Self::Unknown, other) | (other, Self::Unknown) => other,
+            (Self::When(mut conditions), Self::When(others)) => {
+                for condition in others {
+                    if !conditions.contains(&condition) {
+                        conditions.push(condition);
+                    }
+                }
+                Self::When(conditions)
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// The next value of the repeated member `member` is one of `kinds`, or any kind if `None`.
+    fn next(member: &str, kinds: Option<BTreeSet<String>>) -> Self {
+        let iterator = iterator_name(member);
+        Self {
+            expression: match kinds {
+                Some(kinds) => format!(
+                    "{iterator}.peek().is_some_and(|value| {})",
+                    kind_test(&kinds)
+                ),
+                None => format!("{iterator}.peek().is_some()"),
+            },
+            iterator: Some(iterator),
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl Statements<'_> {
+    fn line(&mut self, indent: usize, line: String) {
+        self.lines.push(format!("{}{line}", INDENT.repeat(indent)));
+    }
+
+    fn rule(&mut self, rule: &GrammarRule, indent: usize) {
+        match rule {
+            GrammarRule::String { value } => {
+                self.line(indent, format!("printer.token({value:?})?;"));
+            }
+            GrammarRule::Token { content } => {
+                if let Some(text) = literal_text(content).filter(|text| !text.is_empty()) {
+                    self.line(indent, format!("printer.token({text:?})?;"));
+                }
+            }
+            GrammarRule::ImmediateToken { content } => {
+                if let Some(text) = literal_text(content).filter(|text| !text.is_empty()) {
+                    self.line(indent, format!("printer.immediate_token({text:?})?;"));
+                }
+            }
+            GrammarRule::Alias {
+                value,
+                named: false,
+                ..
+            } => {
+                self.line(indent, format!("printer.token({:?})?;", value.to_string()));
+            }
+            GrammarRule::Field { name, content } => {
+                match self.members.fields.get(name.to_string().as_str()) {
+                    Some((member, Cardinality::Required)) => {
+                        self.line(indent, format!("self.{member}.unparse(printer)?;"));
+                    }
+                    Some((member, Cardinality::Optional)) => {
+                        self.line(indent, format!("if let Some(value) = &self.{member} {{"));
+                        self.line(indent + 1, "value.unparse(printer)?;".to_string());
+                        self.line(indent, "}".to_string());
+                    }
+                    Some((member, Cardinality::Multiple)) => {
+                        let kinds = self.members.kinds(content);
+                        self.next_value(member, kinds, indent)
+                    }
+                    None => {}
+                }
+            }
+            GrammarRule::Symbol { .. } | GrammarRule::Alias { .. } => {
+                if let Some((member, _)) = self.members.children {
+                    let kinds = self.members.kinds(rule);
+                    self.next_value(member, kinds, indent);
+                }
+            }
+            GrammarRule::Sequence { members } => {
+                for (index, member) in members.iter().enumerate() {
+                    self.rule(member, indent);
+                    if index + 1 < members.len() && joins_next(member) {
+                        self.line(indent, "printer.attach();".to_string());
+                    }
+                }
+            }
+            GrammarRule::Choice { members } => match self.members.keyword_choice(members) {
+                Some(keyword) => self.keyword(keyword.member, members, indent),
+                None => self.choice(members, indent),
+            },
+            GrammarRule::Repeat { content } => self.repeat(content, false, indent),
+            GrammarRule::Repeat1 { content } => self.repeat(content, true, indent),
+            GrammarRule::Reserved { content, .. }
+            | GrammarRule::Precedence { content, .. }
+            | GrammarRule::PrecedenceLeftAssoc { content, .. }
+            | GrammarRule::PrecedenceRightAssoc { content, .. }
+            | GrammarRule::PrecedenceDynamic { content, .. } => self.rule(content, indent),
+            GrammarRule::Pattern { .. } | GrammarRule::Blank => {}
+        }
+    }
+
+    ///
+    /// Write `rule` where `test`, if any, is known to hold; it no longer holds once any member
+    /// has been consumed.
+    ///
+    fn guarded(&mut self, test: Option<&str>, rule: &GrammarRule, indent: usize) {
+        let depth = self.known.len();
+        self.known.extend(test.map(str::to_string));
+        self.rule(rule, indent);
+        self.known.truncate(depth);
+    }
+
+    fn next_value(&mut self, member: &str, kinds: Option<BTreeSet<String>>, indent: usize) {
+        self.known.clear();
+        self.iterators.insert(member.to_string());
+        let iterator = iterator_name(member);
+        let next = match kinds {
+            Some(kinds) => format!("{iterator}.next_if(|value| {})", kind_test(&kinds)),
+            None => format!("{iterator}.next()"),
+        };
+        self.line(indent, format!("if let Some(value) = {next} {{"));
+        self.line(indent + 1, "value.unparse(printer)?;".to_string());
+        self.line(indent, "}".to_string());
+    }
+
+    ///
+    /// Write the spelling of a choice of literals held in the keyword member `member`, or the
+    /// first spelling if the member is not set and the choice is not optional.
+    ///
+    fn keyword(&mut self, member: &str, members: &[GrammarRule], indent: usize) {
+        let method = token_method(members.iter().any(is_immediate));
+        if members.contains(&GrammarRule::Blank) {
+            self.line(indent, format!("if let Some(value) = &self.{member} {{"));
+            self.line(indent + 1, format!("printer.{method}(value.kind())?;"));
+            self.line(indent, "}".to_string());
+        } else if let Some(first) = members.iter().find_map(GrammarRule::literal) {
+            self.line(
+                indent,
+                format!(
+                    "printer.{method}(self.{member}.as_ref().map_or({first:?}, |value| value.kind()))?;"
+                ),
+            );
+        }
+    }
+
+    fn choice(&mut self, members: &[GrammarRule], indent: usize) {
+        let optional = members.contains(&GrammarRule::Blank);
+        let mut branches: Vec<(Option<String>, &GrammarRule)> = Vec::new();
+        let mut fallback = None;
+        for member in members
+            .iter()
+            .filter(|member| **member != GrammarRule::Blank)
+        {
+            match self.members.presence(member) {
+                Presence::Always => {
+                    fallback = Some(member);
+                    break;
+                }
+                Presence::When(conditions) => {
+                    // an alternative whose test is known to hold is always chosen.
+                    let test = expression(&conditions);
+                    if self.known.contains(&test) {
+                        fallback = Some(member);
+                        break;
+                    }
+                    let same = branches
+                        .iter()
+                        .position(|(other, _)| other.as_deref() == Some(test.as_str()));
+                    match same {
+                        None => branches.push((Some(test), member)),
+                        // of two alternatives holding the same members, as in `x` or `[ x+ ]`,
+                        // the one writing a single value is only chosen for a single value.
+                        Some(index) if repeats(member) && !repeats(branches[index].1) => {
+                            let remaining = remaining(&conditions);
+                            if remaining.is_empty() {
+                                branches[index].1 = member;
+                            } else {
+                                branches[index].0 = Some(format!("{remaining} == 1 && ({test})"));
+                                branches.insert(index + 1, (Some(test), member));
+                            }
+                        }
+                        // otherwise a later alternative with the same test could never be chosen.
+                        Some(_) => {}
+                    }
+                }
+                Presence::Unknown if !optional && fallback.is_none() => fallback = Some(member),
+                Presence::Unknown => {}
+            }
+        }
+        if let Some(member) = fallback {
+            branches.push((None, member));
+        }
+
+        for (index, (test, member)) in branches.iter().enumerate() {
+            match (index, test) {
+                (0, None) => {
+                    self.rule(member, indent);
+                    return;
+                }
+                (0, Some(test)) => self.line(indent, format!("if {test} {{")),
+                (_, Some(test)) => self.line(indent, format!("}} else if {test} {{")),
+                (_, None) => self.line(indent, "} else {".to_string()),
+            }
+            self.guarded(test.as_deref(), member, indent + 1);
+        }
+        if !branches.is_empty() {
+            self.line(indent, "}".to_string());
+        }
+    }
+
+    fn repeat(&mut self, content: &GrammarRule, at_least_once: bool, indent: usize) {
+        match self.members.presence(content) {
+            Presence::Always => self.rule(content, indent),
+            Presence::Unknown if at_least_once => self.rule(content, indent),
+            Presence::Unknown => {}
+            Presence::When(conditions) => {
+                let test = expression(&conditions);
+                let remaining = remaining(&conditions);
+                if remaining.is_empty() {
+                    // without an iterator to consume this can only be written once.
+                    self.line(indent, format!("if {test} {{"));
+                    self.guarded(Some(&test), content, indent + 1);
+                    self.line(indent, "}".to_string());
+                } else {
+                    // stop if an iteration consumes nothing, so that a choice within the
+                    // repetition that writes no members cannot loop forever.
+                    self.line(indent, format!("while {test} {{"));
+                    self.line(indent + 1, format!("let remaining = {remaining};"));
+                    self.guarded(Some(&test), content, indent + 1);
+                    self.line(indent + 1, format!("if {remaining} == remaining {{"));
+                    self.line(indent + 2, "break;".to_string());
+                    self.line(indent + 1, "}".to_string());
+                    self.line(indent, "}".to_string());
+                }
+            }
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Private Functions
+// ------------------------------------------------------------------------------------------------
+
+fn iterator_name(member: &str) -> String {
+    format!("remaining_{}", member.trim_start_matches("r#"))
+}
+
+fn expression(conditions: &[Condition]) -> String {
+    conditions
+        .iter()
+        .map(|condition| condition.expression.as_str())
+        .collect::<Vec<_>>()
+        .join(" || ")
+}
+
+/// The sum of the lengths of the iterators consumed by `conditions`, empty if there are none.
+fn remaining(conditions: &[Condition]) -> String {
+    conditions
+        .iter()
+        .filter_map(|condition| condition.iterator.as_ref())
+        .map(|iterator| format!("{iterator}.len()"))
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+fn kind_test(kinds: &BTreeSet<String>) -> String {
+    format!(
+        "matches!(value.kind(), {})",
+        kinds
+            .iter()
+            .map(|kind| format!("{kind:?}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    )
+}
+
+fn token_method(immediate: bool) -> &'static str {
+    if immediate {
+        "immediate_token"
+    } else {
+        "token"
+    }
+}
+
+fn repeats(rule: &GrammarRule) -> bool {
+    matches!(
+        rule,
+        GrammarRule::Repeat { .. } | GrammarRule::Repeat1 { .. }
+    ) || rule.contents().into_iter().any(repeats)
+}
+
+///
+/// `true` if the first token written by `rule` must immediately follow the previous token.
+///
+pub(crate) fn is_immediate(rule: &GrammarRule) -> bool {
+    match rule {
+        GrammarRule::ImmediateToken { .. } => true,
+        GrammarRule::Sequence { members } => members.first().is_some_and(is_immediate),
+        GrammarRule::Reserved { content, .. }
+        | GrammarRule::Precedence { content, .. }
+        | GrammarRule::PrecedenceLeftAssoc { content, .. }
+        | GrammarRule::PrecedenceRightAssoc { content, .. }
+        | GrammarRule::PrecedenceDynamic { content, .. } => is_immediate(content),
+        _ => false,
+    }
+}
+
+///
+/// `true` if the token written by `rule` is joined to the token that follows it, as it is either
+/// a `token.immediate` between two others or a sigil such as `token("@")`.
+///
+fn joins_next(rule: &GrammarRule) -> bool {
+    match rule {
+        GrammarRule::ImmediateToken { .. } => true,
+        GrammarRule::Token { content } => match content.as_ref() {
+            GrammarRule::String { value } => {
+                !value.chars().any(|c| c.is_alphanumeric() || c == '_')
+            }
+            _ => false,
+        },
+        GrammarRule::Choice { members } => members
+            .iter()
+            .filter(|member| **member != GrammarRule::Blank)
+            .all(joins_next),
+        GrammarRule::Reserved { content, .. }
+        | GrammarRule::Precedence { content, .. }
+        | GrammarRule::PrecedenceLeftAssoc { content, .. }
+        | GrammarRule::PrecedenceRightAssoc { content, .. }
+        | GrammarRule::PrecedenceDynamic { content, .. } => joins_next(content),
+        _ => false,
+    }
+}
+
+///
+/// The text of a token built only from literals, taking the first alternative of any choice;
+/// `None` if the token includes a pattern or repetition.
+///
+fn literal_text(rule: &GrammarRule) -> Option<String> {
+    match rule {
+        GrammarRule::String { value } => Some(value.clone()),
+        GrammarRule::Blank => Some(String::new()),
+        GrammarRule::Sequence { members } => members.iter().map(literal_text).collect(),
+        GrammarRule::Choice { members } => members.first().and_then(literal_text),
+        GrammarRule::Token { content }
+        | GrammarRule::ImmediateToken { content }
+        | GrammarRule::Reserved { content, .. }
+        | GrammarRule::Precedence { content, .. }
+        | GrammarRule::PrecedenceLeftAssoc { content, .. }
+        | GrammarRule::PrecedenceRightAssoc { content, .. }
+        | GrammarRule::PrecedenceDynamic { content, .. } => literal_text(content),
+        _ => None,
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// Unit Tests
+// ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::{AstFile, render_with};
+
+    /// The body of the rendered `Unparse::unparse` method for the AST type `type_name`.
+    fn unparse_of(rendered: &str, type_name: &str) -> String {
+        let start = rendered
+            .find(&format!("impl Unparse for {type_name} {{\n"))
+            .expect("no Unparse implementation for the type");
+        let body = &rendered[start..];
+        body[..body.find("\n    }\n").unwrap()].to_string()
+    }
+
+    #[test]
+    fn test_keyword_choice_writes_spelling() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        let unparse = unparse_of(&rendered, "DatatypeDef");
+        assert!(unparse.contains(
+            "        printer.token(\"datatype\")?;\n        self.name.unparse(printer)?;\n        printer.token(self.variant.as_ref().map_or(\"<-\", |value| value.kind()))?;"
+        ));
+        assert!(!unparse.contains("printer.token(\"<-\")?;"));
+    }
+
+    #[test]
+    fn test_children_matched_by_kind() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        let unparse = unparse_of(&rendered, "DimensionBody");
+        assert!(unparse.contains(
+            "        while remaining_children.peek().is_some_and(|value| matches!(value.kind(), \"dimension_parent\")) {\n            let remaining = remaining_children.len();\n            if let Some(value) = remaining_children.next_if(|value| matches!(value.kind(), \"dimension_parent\")) {"
+        ));
+        assert!(unparse.contains(
+            "            if let Some(value) = remaining_children.next_if(|value| matches!(value.kind(), \"member\")) {"
+        ));
+
+        let unparse = unparse_of(&rendered, "Equation");
+        assert!(unparse.contains(
+            "        self.lhs.unparse(printer)?;\n        if let Some(value) = remaining_child.next_if(|value| matches!(value.kind(), \"op_equality\")) {\n            value.unparse(printer)?;\n        }\n        self.rhs.unparse(printer)?;"
+        ));
+
+        let unparse = unparse_of(&rendered, "DatatypeDef");
+        assert!(unparse.contains(
+            "        if self.restriction.as_ref().is_some_and(|value| matches!(value.kind(), \"datatype_type_restrictions\")) {"
+        ));
+    }
+
+    #[test]
+    fn test_immediate_token_suppresses_whitespace() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert_eq!(
+            unparse_of(&rendered, "QualifiedIdentifier"),
+            [
+                "impl Unparse for QualifiedIdentifier {",
+                "    fn unparse(&self, printer: &mut Printer<'_>) -> fmt::Result {",
+                "        printer.enter(\"qualified_identifier\");",
+                "        self.module.unparse(printer)?;",
+                "        printer.immediate_token(\":\")?;",
+                "        printer.attach();",
+                "        self.member.unparse(printer)?;",
+                "        printer.leave(\"qualified_identifier\");",
+                "        Ok(())",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_leaf_text_written_verbatim() {
+        let rendered = render_with(&AstFile, |arguments| arguments);
+        assert_eq!(
+            unparse_of(&rendered, "BooleanFalsity"),
+            [
+                "impl Unparse for BooleanFalsity {",
+                "    fn unparse(&self, printer: &mut Printer<'_>) -> fmt::Result {",
+                "        printer.enter(\"boolean_falsity\");",
+                "        printer.token(&self.text)?;",
+                "        printer.leave(\"boolean_falsity\");",
+                "        Ok(())",
+            ]
+            .join("\n")
+        );
+    }
+}
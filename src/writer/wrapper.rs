@@ -1,15 +1,116 @@
 /*!
-One-line description.
+Generates light-weight, type-safe, wrappers around the tree-sitter CST for a grammar.
+
+Every regular (non-terminal) node in `node-types.json` becomes a *compound node* with one accessor
+per field, plus a `child` accessor for any named children not assigned to a field, or a
+`children` iterator where more than one may appear. Every named terminal becomes a *value node*
+holding the node's text, and the node marked as the grammar's root becomes the *root node* which
+owns the parsed `Tree`. Each super-type becomes an enum with one variant per sub-type, where a
+sub-type that is itself a super-type is represented by its own, nested, enum.
+
+A compound node with fields or children also has a `<Node>Parts` struct, with one public member
+per field and one for its children, returned by `parts` and `into_parts` so that the node may be
+destructured in a single statement; the root node only has `parts` as its members borrow its tree,
+unless the tree is shared as described below.
+
+Any field or child slot that accepts more than one node type is given a synthesized *choice* enum,
+named for the parent node and field (`<Parent><Field>`) or the parent node's children
+(`<Parent>Child`). Where [`Arguments::with_share_choices`] is set, slots that accept an identical
+set of types share a single choice enum named for its members (`<A>Or<B>`).
+
+A field or child slot that accepts only anonymous tokens, such as the `operator` of a binary
+expression, is instead given a closed *operator* enum with one unit variant per token, named from
+the token text as for the variants of a choice. Operator enums are `Copy`, convert to and from
+their token text with `as_str` and `FromStr`, and as they hold no node they do not implement
+`Spanned`.
+
+Literal keywords in a node's rules that are not within a field are found by
+[`GrammarRule::keywords`]. A compound node has a `has_<keyword>_keyword` method for each optional
+keyword, or `has_token_literal` where the keyword's name would only be its code points, and a
+`variant` method returning an operator-style `<Node>Variant` enum for each choice between
+alternative spellings of a keyword, such as `choice("and", "∧")`. Keywords that are also among
+the [`GrammarRule::required_literals`] of the node are skipped, as they are always present.
+
+The generated file also contains a per-grammar error enum, `<Grammar>Error`, used by the checked
+`TypedNode::try_from_node` constructor. Where [`Arguments::with_fallible`] is set, accessors
+return a `Result` using this error rather than panicking on a malformed tree. Where
+[`Arguments::with_field_ids`] is set, accessors find their field by the id cached in the constants
+file's `FieldName` enum, with `Node::child_by_field_id`, rather than looking up its name, and
+un-named children, keywords, and the children folded by `Fold` are told apart by field id.
+
+Value nodes hold the text of a terminal; where [`Arguments::with_value_type`] maps a terminal kind
+to a Rust type implementing `FromStr` the value node also has a typed `value()` method. By default
+value nodes copy their text into a `String`, where [`Arguments::with_value_mode`] selects
+[`ValueMode::Borrowed`](super::ValueMode::Borrowed) they instead keep the node and borrow their
+text from the source buffer. In either mode the text is checked to be UTF-8 once, as the value is
+built, so that `text` cannot fail.
+
+The generated `Visitor` trait has a `visit_<type>` method for every generated type, named for
+the snake-case type name, whose default implementation calls the matching `walk_<type>` function
+to visit the node's fields and un-named children in turn, in the style of `syn::visit`. The
+generated `Fold` trait has a matching `fold_<type>` method for every type, returning the
+implementation's `Output` type; by default compound nodes fold their fields and children, in the
+order they appear in the source, and combine the results with `Fold::fold_node`, and value nodes
+fold their text with `Fold::fold_text`. Where [`Arguments::with_fallible`] is set each method
+returns a `Result`, so that an error from any accessor is returned by the fold.
+[`AstFile`](super::AstFile) generates a separate fold whose methods return the AST type of each
+node.
+
+Each generated type carries the node kinds it represents, either as a single `KIND` constant or
+the `TypedNode::KINDS` list, so that the checked constructors, `TryFrom`, and `NodeExt::cast`
+reject nodes of any other kind. Every generated type implements the generated `Spanned` trait,
+giving its byte range, start and end points, and text; value nodes in the default owned mode
+copy these from the node so that they remain available after the tree is dropped. Value nodes
+are equal when their text is equal, while `Spanned::eq_spanned` also compares byte ranges. The
+root and compound nodes use these kinds to search their descendants by type, walking the tree
+with a `TreeCursor` and optionally not descending into the kinds of a given super-type.
+
+The root node reports the `ERROR` and `MISSING` nodes in its tree as `SyntaxError` values, each
+with the kind of the nearest enclosing regular node and the concrete kinds that node allows at the
+error's position, taken from the fields and children listed in `node-types.json`.
+
+From the fields and children in `node-types.json` the generator builds a reverse containment
+index, the node types that may contain each node type. Compound nodes have a `parent` method
+returning their single possible parent type directly, or otherwise a `<Type>Parent` enum of the
+possible parent types; the root node is not included as a parent as it wraps the tree itself.
+
+The named symbols in the grammar's `extras` are treated as comments. Compound nodes provide
+`leading_comments` and `trailing_comments`, attaching to a node the run of comment siblings
+directly before it, up to a blank line, and the comment siblings starting on the row it ends on.
+
+Accessors are generated in the order their fields appear in `grammar.json`, after resolving the
+grammar's hidden, inlined, and aliased rules: fields within hidden and inlined rules are hoisted
+into the visible node that uses them, and an aliased rule contributes its fields to the node named
+by the alias rather than the rule itself.
+
+Where [`Arguments::with_shared_tree`] is set, wrappers hold a `SharedTree`, an
+`Arc<(Tree, Arc<[u8]>)>`, with their node's descendant index rather than a borrowed `Node`, and
+move a cursor from the root of the tree to that index when the node is needed; this steps over
+whole subtrees, so costs time in proportion to the node's depth. The generated types then have
+no lifetimes and are `Send + Sync + 'static`, with the same accessors as the borrowed wrappers;
+the root node is created by `TypedRootNode::from_tree` from a `Tree` and an `Arc<[u8]>` source.
 
  */
 
 use crate::{
     error::Error,
-    reader::GrammarFile,
-    writer::{Arguments, Output},
+    reader::{
+        GrammarFile, GrammarSources, NodeTypesFile,
+        grammar::{GrammarRule, Keyword},
+        node_types::{NodeChildren, NodeType, NodeTypeDefinition},
+    },
+    writer::{
+        Arguments, Output,
+        naming::{constant_name, field_ident, is_spelled_out, method_name, token_name, type_name},
+    },
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
 };
-use std::io::Write;
 use tera::Tera;
+use tracing::warn;
 
 // ------------------------------------------------------------------------------------------------
 // Public Types
@@ -19,16 +120,208 @@ use tera::Tera;
 pub struct WrapperFile;
 
 // ------------------------------------------------------------------------------------------------
-// Public Functions
+// Private Types
 // ------------------------------------------------------------------------------------------------
 
-// ------------------------------------------------------------------------------------------------
-// Private Macros
-// ------------------------------------------------------------------------------------------------
+const ROOT_LIFETIME: &str = "'_";
+const NODE_LIFETIME: &str = "'t";
+pub(crate) const TOKEN_VALUE: &str = "TokenValue";
+pub(crate) const ANY_NODE: &str = "AnyNode";
+const SUPPORT_TYPE_NAMES: &[&str] = &[
+    TOKEN_VALUE,
+    ANY_NODE,
+    "Descendants",
+    "FieldChildren",
+    "Fold",
+    "SharedNode",
+    "SharedTree",
+    "Spanned",
+    "SyntaxError",
+    "SyntaxErrors",
+    "UnknownOperator",
+    "UnnamedChildren",
+    "Visitor",
+];
 
-// ------------------------------------------------------------------------------------------------
-// Private Types
-// ------------------------------------------------------------------------------------------------
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct CompoundNode {
+    pub(crate) kind: String,
+    pub(crate) kind_constant: String,
+    pub(crate) type_name: String,
+    pub(crate) lifetime: String,
+    pub(crate) accessors: Vec<Accessor>,
+    pub(crate) keywords: Vec<KeywordAccessor>,
+    pub(crate) children: Option<Children>,
+    pub(crate) parts: Option<Parts>,
+    pub(crate) parent: Option<String>,
+    pub(crate) method_suffix: String,
+}
+
+/// The struct returned by a compound node's `parts` method, with one member per field and one
+/// for the un-named children.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Parts {
+    pub(crate) type_name: String,
+    pub(crate) generics: String,
+    pub(crate) members: Vec<PartsMember>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct PartsMember {
+    pub(crate) name: String,
+    pub(crate) method_name: String,
+    pub(crate) cardinality: Cardinality,
+    pub(crate) item_type: String,
+}
+
+/// An accessor for a literal keyword of a compound node that is not within a field; either a
+/// `has_` test for an optional keyword, or a `variant` accessor returning which of several
+/// alternative spellings is present.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct KeywordAccessor {
+    pub(crate) doc: String,
+    pub(crate) optional: bool,
+    pub(crate) method_name: String,
+    pub(crate) kinds: Vec<String>,
+    pub(crate) variant_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Accessor {
+    pub(crate) method_name: String,
+    pub(crate) field_name: String,
+    pub(crate) field_constant: String,
+    pub(crate) field_variant: String,
+    pub(crate) cardinality: Cardinality,
+    pub(crate) item_type: String,
+    pub(crate) item_name: String,
+    pub(crate) method_suffix: String,
+}
+
+/// The named children of a compound node that are not assigned to any field, accessed by a
+/// `child` method where there is at most one, or otherwise a `children` method.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Children {
+    pub(crate) method_name: String,
+    pub(crate) cardinality: Cardinality,
+    pub(crate) item_type: String,
+    pub(crate) item_name: String,
+    pub(crate) method_suffix: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Cardinality {
+    Required,
+    Optional,
+    Multiple,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ValueNode {
+    pub(crate) kind: Option<String>,
+    pub(crate) kind_constant: Option<String>,
+    pub(crate) type_name: String,
+    pub(crate) generics: String,
+    pub(crate) borrowed: bool,
+    pub(crate) value_type: Option<String>,
+    pub(crate) method_suffix: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct EnumNode {
+    pub(crate) doc: String,
+    pub(crate) kind_constant: Option<String>,
+    pub(crate) kinds: Vec<String>,
+    pub(crate) type_name: String,
+    pub(crate) generics: String,
+    pub(crate) variants: Vec<Variant>,
+    pub(crate) method_suffix: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Variant {
+    pub(crate) name: String,
+    pub(crate) kind_pattern: String,
+    pub(crate) item_type: String,
+    pub(crate) item_name: String,
+    pub(crate) is_super_type: bool,
+    pub(crate) method_suffix: String,
+}
+
+/// A closed enum over the anonymous tokens accepted by a field or children slot.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct OperatorNode {
+    pub(crate) doc: String,
+    pub(crate) kinds: Vec<String>,
+    pub(crate) type_name: String,
+    pub(crate) variants: Vec<OperatorVariant>,
+    pub(crate) method_suffix: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct OperatorVariant {
+    pub(crate) name: String,
+    pub(crate) kind_pattern: String,
+}
+
+/// The node kinds that may appear as children of the regular node `kind_constant`, used to
+/// describe what was expected at the position of a syntax error.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ExpectedKinds {
+    pub(crate) kind_constant: String,
+    pub(crate) fields: Vec<FieldKinds>,
+    pub(crate) all_kinds: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct FieldKinds {
+    pub(crate) field_constant: String,
+    pub(crate) kinds: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TypeRef {
+    name: String,
+    borrowed: bool,
+}
+
+///
+/// The typed nodes generated for a grammar, this is shared with the AST generator so that the
+/// lowering it generates refers to exactly the types in the wrapper.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct WrapperModel {
+    pub(crate) error_type: String,
+    pub(crate) root_node: Option<CompoundNode>,
+    pub(crate) compound_nodes: Vec<CompoundNode>,
+    pub(crate) super_type_nodes: Vec<EnumNode>,
+    pub(crate) choice_nodes: Vec<EnumNode>,
+    pub(crate) operator_nodes: Vec<OperatorNode>,
+    pub(crate) keyword_nodes: Vec<OperatorNode>,
+    pub(crate) parent_nodes: Vec<EnumNode>,
+    pub(crate) value_nodes: Vec<ValueNode>,
+    pub(crate) expected_kinds: Vec<ExpectedKinds>,
+    pub(crate) comment_kinds: Vec<String>,
+}
+
+/// Identifies a field, or the un-named children, of a parent node kind.
+type Slot = (String, Option<String>);
+
+struct TypeResolver<'a> {
+    grammar: &'a GrammarFile,
+    node_types: &'a NodeTypesFile,
+    borrowed_values: bool,
+    shared_tree: bool,
+    choices: BTreeMap<Slot, TypeRef>,
+    choice_nodes: Vec<EnumNode>,
+    operator_nodes: Vec<OperatorNode>,
+    keywords: BTreeMap<String, Vec<KeywordAccessor>>,
+    keyword_nodes: Vec<OperatorNode>,
+    parents: BTreeMap<String, TypeRef>,
+    parent_nodes: Vec<EnumNode>,
+    parts: BTreeMap<String, String>,
+}
 
 // ------------------------------------------------------------------------------------------------
 // Implementations
@@ -37,7 +330,7 @@ pub struct WrapperFile;
 impl Output for WrapperFile {
     const DEFAULT_FILE_NAME: &str = "wrapper";
     const DEFAULT_DIRECTORY: &str = "bindings";
-    type InputFile = GrammarFile;
+    type InputFile = GrammarSources;
 
     fn write<W>(&self, arguments: Arguments<Self::InputFile>, w: &mut W) -> Result<(), Error>
     where
@@ -45,11 +338,24 @@ impl Output for WrapperFile {
     {
         let tera = Tera::new("templates/**/wrapper.*")?;
 
+        let model = WrapperModel::new(&arguments)?;
+
         let mut context = tera::Context::new();
-        context.insert("name", arguments.input_file.name());
-        context.insert("root_node", &String::new());
-        context.insert("compound_nodes", &Vec::<String>::default());
-        context.insert("value_nodes", &vec!["IdentifierValue", "TokenValue"]);
+        context.insert("name", arguments.input_file.grammar().name());
+        context.insert("error_type", &model.error_type);
+        context.insert("fallible", &arguments.fallible());
+        context.insert("field_ids", &arguments.field_ids());
+        context.insert("shared_tree", &arguments.shared_tree());
+        context.insert("root_node", &model.root_node);
+        context.insert("compound_nodes", &model.compound_nodes);
+        context.insert("super_type_nodes", &model.super_type_nodes);
+        context.insert("choice_nodes", &model.choice_nodes);
+        context.insert("operator_nodes", &model.operator_nodes);
+        context.insert("keyword_nodes", &model.keyword_nodes);
+        context.insert("parent_nodes", &model.parent_nodes);
+        context.insert("value_nodes", &model.value_nodes);
+        context.insert("expected_kinds", &model.expected_kinds);
+        context.insert("comment_kinds", &model.comment_kinds);
 
         let rendered = tera
             .render(&format!("wrapper.{}", arguments.for_language), &context)
@@ -60,10 +366,1212 @@ impl Output for WrapperFile {
     }
 }
 
+// ------------------------------------------------------------------------------------------------
+
+impl WrapperModel {
+    pub(crate) fn new(arguments: &Arguments<GrammarSources>) -> Result<Self, Error> {
+        let grammar = arguments.input_file.grammar();
+        let node_types = arguments.input_file.node_types();
+        let resolver = TypeResolver::new(arguments);
+        // Accessors on a shared node return iterators borrowing the node, rather than its tree.
+        let node_lifetime = if arguments.shared_tree() {
+            ROOT_LIFETIME
+        } else {
+            NODE_LIFETIME
+        };
+
+        let root_node = node_types
+            .root_definition()
+            .map(|defn| resolver.compound_node(defn, ROOT_LIFETIME));
+        let compound_nodes = node_types
+            .regular_definitions()
+            .filter(|defn| !defn.is_root())
+            .map(|defn| resolver.compound_node(defn, node_lifetime))
+            .collect::<Vec<_>>();
+        let super_type_nodes = node_types
+            .super_type_definitions()
+            .map(|defn| resolver.super_type_node(defn))
+            .collect::<Vec<_>>();
+
+        let terminal_names = node_types.named_terminal_node_type_names();
+        if let Some(kind) = arguments
+            .value_types()
+            .keys()
+            .find(|kind| !terminal_names.contains(kind))
+        {
+            return Err(Error::Unknown {
+                message: format!("cannot map `{kind}` to a value type, it is not a named terminal"),
+            });
+        }
+        let value_nodes = node_types
+            .named_terminal_definitions()
+            .map(|defn| ValueNode {
+                kind: Some(defn.node_type_name().clone()),
+                kind_constant: Some(resolver.kind_pattern(defn.node_type())),
+                type_name: value_type_name(defn.node_type_name()),
+                generics: resolver.value_generics(),
+                borrowed: resolver.borrowed_values,
+                value_type: arguments.value_type(defn.node_type_name()).cloned(),
+                method_suffix: method_name(value_type_name(defn.node_type_name())),
+            })
+            .chain(std::iter::once(ValueNode {
+                kind: None,
+                kind_constant: None,
+                type_name: TOKEN_VALUE.to_string(),
+                generics: resolver.value_generics(),
+                borrowed: resolver.borrowed_values,
+                value_type: None,
+                method_suffix: method_name(TOKEN_VALUE),
+            }))
+            .collect::<Vec<_>>();
+        let expected_kinds = node_types
+            .regular_definitions()
+            .map(|defn| resolver.expected_kinds(defn))
+            .collect::<Vec<_>>();
+        let comment_kinds = grammar
+            .extras()
+            .filter_map(|rule| match rule {
+                GrammarRule::Symbol { name } => Some(NodeType::new_named(name.to_string())),
+                _ => None,
+            })
+            .filter(|node_type| node_types.definition(node_type).is_some())
+            .map(|node_type| resolver.kind_pattern(&node_type))
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            error_type: format!("{}Error", type_name(grammar.name())),
+            root_node,
+            compound_nodes,
+            super_type_nodes,
+            choice_nodes: resolver.choice_nodes,
+            operator_nodes: resolver.operator_nodes,
+            keyword_nodes: resolver.keyword_nodes,
+            parent_nodes: resolver.parent_nodes,
+            value_nodes,
+            expected_kinds,
+            comment_kinds,
+        })
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl<'a> TypeResolver<'a> {
+    fn new(arguments: &'a Arguments<GrammarSources>) -> Self {
+        let node_types = arguments.input_file.node_types();
+        let share_choices = arguments.share_choices();
+        let mut resolver = Self {
+            grammar: arguments.input_file.grammar(),
+            node_types,
+            borrowed_values: arguments.value_mode().is_borrowed(),
+            shared_tree: arguments.shared_tree(),
+            choices: Default::default(),
+            choice_nodes: Default::default(),
+            operator_nodes: Default::default(),
+            keywords: Default::default(),
+            keyword_nodes: Default::default(),
+            parents: Default::default(),
+            parent_nodes: Default::default(),
+            parts: Default::default(),
+        };
+
+        let mut reserved_names = node_types
+            .definitions()
+            .filter(|defn| defn.node_type().is_named())
+            .map(|defn| {
+                if defn.is_root() {
+                    compound_type_name(defn.node_type_name())
+                } else {
+                    resolver.type_ref(defn.node_type()).name
+                }
+            })
+            .chain(SUPPORT_TYPE_NAMES.iter().map(|name| name.to_string()))
+            .collect::<BTreeSet<_>>();
+        let mut shared: BTreeMap<Vec<NodeType>, TypeRef> = Default::default();
+
+        for defn in node_types.regular_definitions() {
+            let Some(regular) = defn.kind().as_regular() else {
+                continue;
+            };
+            let parent = defn.node_type_name();
+            let slots = regular
+                .fields()
+                .into_iter()
+                .flatten()
+                .map(|(field, children)| (Some(field), children))
+                .chain(regular.children().map(|children| (None, children)));
+            for (field, children) in
+                slots.filter(|(_, children)| children.type_count() > 1 || is_operator(children))
+            {
+                let mut types = children.types().cloned().collect::<Vec<_>>();
+                types.sort();
+                let operator = is_operator(children);
+
+                let type_ref = match shared.get(&types) {
+                    Some(type_ref) if share_choices => type_ref.clone(),
+                    _ => {
+                        let name = if share_choices {
+                            types
+                                .iter()
+                                .map(variant_name)
+                                .collect::<Vec<_>>()
+                                .join("Or")
+                        } else {
+                            format!(
+                                "{}{}",
+                                type_name(parent),
+                                field.map(type_name).unwrap_or_else(|| "Child".to_string())
+                            )
+                        };
+                        let name = if reserved_names.contains(&name) {
+                            format!("{name}Choice")
+                        } else {
+                            name
+                        };
+                        reserved_names.insert(name.clone());
+                        let accepted = if operator { "tokens" } else { "node types" };
+                        let doc = match field {
+                            Some(field) if !share_choices => format!(
+                                "The {accepted} accepted by the field `{field}` of the grammar node `{parent}`."
+                            ),
+                            None if !share_choices => format!(
+                                "The {accepted} accepted as children of the grammar node `{parent}`."
+                            ),
+                            _ => format!(
+                                "A choice of {accepted} shared by multiple fields or children."
+                            ),
+                        };
+                        let type_ref = if operator {
+                            let operator = resolver.operator_node(doc, name, &types);
+                            let type_ref = TypeRef {
+                                name: operator.type_name.clone(),
+                                borrowed: false,
+                            };
+                            resolver.operator_nodes.push(operator);
+                            type_ref
+                        } else {
+                            let choice = resolver.enum_node(doc, None, name, &types);
+                            let type_ref = TypeRef {
+                                name: choice.type_name.clone(),
+                                borrowed: !choice.generics.is_empty(),
+                            };
+                            resolver.choice_nodes.push(choice);
+                            type_ref
+                        };
+                        shared.insert(types, type_ref.clone());
+                        type_ref
+                    }
+                };
+                resolver
+                    .choices
+                    .insert((parent.clone(), field.cloned()), type_ref);
+            }
+        }
+
+        for defn in node_types.regular_definitions() {
+            let kind = defn.node_type_name();
+            let rules = resolver.grammar.visible_rules(kind);
+            // a literal the node always contains would be found by `keyword_child` whether or not
+            // the optional keyword is present, so it cannot be tested for.
+            let required = rules
+                .iter()
+                .flat_map(|rule| rule.required_literals())
+                .collect::<BTreeSet<_>>();
+            let mut found: Vec<(Vec<String>, bool, bool)> = Vec::new();
+            for rule in &rules {
+                for keyword in rule.keywords() {
+                    let keyword = match keyword {
+                        Keyword::Optional(value) => (vec![value.to_string()], true, false),
+                        Keyword::Alternatives { values, optional } => (
+                            values.into_iter().map(str::to_string).collect(),
+                            optional,
+                            true,
+                        ),
+                    };
+                    if !found.contains(&keyword)
+                        && !keyword
+                            .0
+                            .iter()
+                            .any(|value| required.contains(value.as_str()))
+                    {
+                        found.push(keyword);
+                    }
+                }
+            }
+            let mut accessors = Vec::new();
+            let mut variants = 0;
+            let mut literals = 0;
+            for (values, optional, alternatives) in found {
+                let types = values
+                    .iter()
+                    .map(|value| NodeType::new_unnamed(value.clone()))
+                    .collect::<Vec<_>>();
+                let kinds = types
+                    .iter()
+                    .map(|node_type| resolver.kind_pattern(node_type))
+                    .collect();
+                if alternatives {
+                    variants += 1;
+                    let suffix = if variants == 1 {
+                        String::new()
+                    } else {
+                        variants.to_string()
+                    };
+                    let mut name = format!("{}Variant{suffix}", type_name(kind));
+                    while reserved_names.contains(&name) {
+                        name = format!("{name}Choice");
+                    }
+                    reserved_names.insert(name.clone());
+                    let doc = format!(
+                        "The alternative spellings of a keyword in the grammar node `{kind}`."
+                    );
+                    let keyword_node = resolver.operator_node(doc, name.clone(), &types);
+                    resolver.keyword_nodes.push(keyword_node);
+                    accessors.push(KeywordAccessor {
+                        doc: format!("Returns which spelling of `{name}` this node contains"),
+                        optional,
+                        method_name: if suffix.is_empty() {
+                            "variant".to_string()
+                        } else {
+                            format!("variant_{suffix}")
+                        },
+                        kinds,
+                        variant_type: Some(name),
+                    });
+                } else {
+                    let value = &values[0];
+                    let noun = if value.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        "keyword"
+                    } else {
+                        "token"
+                    };
+                    // a token named only by its code points is tested by `has_token_literal`.
+                    let method_name = if is_spelled_out(value) {
+                        format!("has_{}_{noun}", method_name(token_name(value)))
+                    } else {
+                        literals += 1;
+                        if literals == 1 {
+                            "has_token_literal".to_string()
+                        } else {
+                            format!("has_token_literal_{literals}")
+                        }
+                    };
+                    accessors.push(KeywordAccessor {
+                        doc: format!(
+                            "Returns `true` if this node contains the optional {noun} `{value}`."
+                        ),
+                        optional,
+                        method_name,
+                        kinds,
+                        variant_type: None,
+                    });
+                }
+            }
+            if !accessors.is_empty() {
+                resolver.keywords.insert(kind.clone(), accessors);
+            }
+        }
+
+        // The reverse containment index, from each named node type to the regular node types
+        // that may contain it; the root is not included as it cannot be wrapped as a node.
+        let mut containers: BTreeMap<NodeType, Vec<NodeType>> = Default::default();
+        for defn in node_types
+            .regular_definitions()
+            .filter(|defn| !defn.is_root())
+        {
+            let Some(regular) = defn.kind().as_regular() else {
+                continue;
+            };
+            let child_types = regular
+                .fields()
+                .into_iter()
+                .flat_map(|fields| fields.values())
+                .chain(regular.children())
+                .flat_map(|children| children.types())
+                .flat_map(|node_type| resolver.concrete_types(node_type))
+                .filter(|node_type| node_type.is_named());
+            for child_type in child_types {
+                let parents = containers.entry(child_type).or_default();
+                if !parents.contains(defn.node_type()) {
+                    parents.push(defn.node_type().clone());
+                }
+            }
+        }
+        for (child_type, parents) in containers {
+            let type_ref = if let [parent] = parents.as_slice() {
+                resolver.type_ref(parent)
+            } else {
+                let name = format!("{}Parent", type_name(child_type.node_type()));
+                let name = if reserved_names.contains(&name) {
+                    format!("{name}Choice")
+                } else {
+                    name
+                };
+                reserved_names.insert(name.clone());
+                let doc = format!(
+                    "The node types that may contain the grammar node `{}` as a field or child.",
+                    child_type.node_type()
+                );
+                let parent_node = resolver.enum_node(doc, None, name, &parents);
+                let type_ref = TypeRef {
+                    name: parent_node.type_name.clone(),
+                    borrowed: !resolver.shared_tree,
+                };
+                resolver.parent_nodes.push(parent_node);
+                type_ref
+            };
+            resolver
+                .parents
+                .insert(child_type.node_type().clone(), type_ref);
+        }
+
+        for defn in node_types.regular_definitions() {
+            let Some(regular) = defn.kind().as_regular() else {
+                continue;
+            };
+            if regular.fields().is_none_or(|fields| fields.is_empty())
+                && regular.children().is_none()
+            {
+                continue;
+            }
+            let kind = defn.node_type_name();
+            let mut name = format!("{}Parts", type_name(kind));
+            while reserved_names.contains(&name) {
+                name = format!("{name}Choice");
+            }
+            reserved_names.insert(name.clone());
+            resolver.parts.insert(kind.clone(), name);
+        }
+        resolver
+    }
+
+    fn compound_node(&self, defn: &NodeTypeDefinition, lifetime: &str) -> CompoundNode {
+        let parent = defn.node_type_name();
+        // Parts members always use the named lifetime, as the root node's parts borrow its tree.
+        let mut members: Vec<PartsMember> = Vec::new();
+        let mut borrowed = false;
+        let accessors = defn
+            .kind()
+            .as_regular()
+            .and_then(|regular| regular.fields())
+            .map(|fields| {
+                self.ordered_fields(parent, fields)
+                    .into_iter()
+                    .map(|(field_name, children)| {
+                        let item_type = self.slot_type(parent, Some(field_name), children);
+                        let accessor = Accessor {
+                            method_name: format!("field_{field_name}"),
+                            field_name: field_name.clone(),
+                            field_constant: constant_name(field_name),
+                            field_variant: type_name(field_name),
+                            cardinality: Cardinality::from(children),
+                            item_type: item_type.render(lifetime),
+                            item_name: item_type.name.clone(),
+                            method_suffix: method_name(&item_type.name),
+                        };
+                        borrowed |= item_type.borrowed;
+                        members.push(PartsMember {
+                            name: field_ident(field_name),
+                            method_name: accessor.method_name.clone(),
+                            cardinality: accessor.cardinality,
+                            item_type: item_type.render(NODE_LIFETIME),
+                        });
+                        accessor
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let children = defn
+            .kind()
+            .as_regular()
+            .and_then(|regular| regular.children())
+            .map(|children| {
+                let item_type = self.slot_type(parent, None, children);
+                let cardinality = Cardinality::from(children);
+                let children = Children {
+                    method_name: if cardinality == Cardinality::Multiple {
+                        "children"
+                    } else {
+                        "child"
+                    }
+                    .to_string(),
+                    cardinality,
+                    item_type: item_type.render(lifetime),
+                    item_name: item_type.name.clone(),
+                    method_suffix: method_name(&item_type.name),
+                };
+                let name = if members
+                    .iter()
+                    .any(|member| member.name == children.method_name)
+                {
+                    format!("unnamed_{}", children.method_name)
+                } else {
+                    children.method_name.clone()
+                };
+                borrowed |= item_type.borrowed;
+                members.push(PartsMember {
+                    name,
+                    method_name: children.method_name.clone(),
+                    cardinality,
+                    item_type: item_type.render(NODE_LIFETIME),
+                });
+                children
+            });
+        let parts = self.parts.get(parent).map(|type_name| Parts {
+            type_name: type_name.clone(),
+            generics: if borrowed {
+                format!("<{NODE_LIFETIME}, 's>")
+            } else {
+                String::new()
+            },
+            members,
+        });
+        let type_name = compound_type_name(parent);
+        CompoundNode {
+            kind: parent.clone(),
+            kind_constant: self.kind_pattern(defn.node_type()),
+            method_suffix: method_name(&type_name),
+            type_name,
+            lifetime: lifetime.to_string(),
+            accessors,
+            keywords: self.keywords.get(parent).cloned().unwrap_or_default(),
+            children,
+            parts,
+            parent: self
+                .parents
+                .get(parent)
+                .map(|type_ref| type_ref.render(lifetime)),
+        }
+    }
+
+    fn expected_kinds(&self, defn: &NodeTypeDefinition) -> ExpectedKinds {
+        let regular = defn.kind().as_regular();
+        let fields = regular
+            .and_then(|regular| regular.fields())
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(field_name, children)| FieldKinds {
+                        field_constant: constant_name(field_name),
+                        kinds: self.slot_kinds([children]),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let all_kinds = self.slot_kinds(
+            regular
+                .and_then(|regular| regular.fields())
+                .into_iter()
+                .flat_map(|fields| fields.values())
+                .chain(regular.and_then(|regular| regular.children())),
+        );
+        ExpectedKinds {
+            kind_constant: self.kind_pattern(defn.node_type()),
+            fields,
+            all_kinds,
+        }
+    }
+
+    ///
+    /// The concrete kinds accepted by any of the given slots, in order and without duplicates.
+    ///
+    fn slot_kinds(&self, slots: impl IntoIterator<Item = &'a NodeChildren>) -> Vec<String> {
+        let mut kinds: Vec<String> = Default::default();
+        for kind in slots
+            .into_iter()
+            .flat_map(|children| children.types())
+            .flat_map(|node_type| self.concrete_kinds(node_type))
+        {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+
+    ///
+    /// Order the fields of the node `parent` as they first appear in the grammar's visible rules
+    /// for that node; any field the grammar does not account for is reported and placed last.
+    ///
+    fn ordered_fields<'f>(
+        &self,
+        parent: &str,
+        fields: &'f BTreeMap<String, NodeChildren>,
+    ) -> Vec<(&'f String, &'f NodeChildren)> {
+        let grammar_order = self.grammar.visible_field_names(parent);
+        let mut ordered = fields
+            .iter()
+            .map(|(field_name, children)| {
+                let position = grammar_order.iter().position(|name| name == field_name);
+                if position.is_none() {
+                    warn!(
+                        "field `{field_name}` of node `{parent}` was not found in the grammar rules"
+                    );
+                }
+                (position.unwrap_or(usize::MAX), field_name, children)
+            })
+            .collect::<Vec<_>>();
+        ordered.sort_by_key(|(position, _, _)| *position);
+        ordered
+            .into_iter()
+            .map(|(_, field_name, children)| (field_name, children))
+            .collect()
+    }
+
+    fn super_type_node(&self, defn: &NodeTypeDefinition) -> EnumNode {
+        let subtypes = defn
+            .kind()
+            .as_super_type()
+            .map(|super_type| super_type.subtypes().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        self.enum_node(
+            format!(
+                "The grammar *super-type* `{}`, with one variant for each of its sub-types.",
+                defn.node_type_name()
+            ),
+            Some(self.kind_pattern(defn.node_type())),
+            type_name(defn.node_type_name()),
+            &subtypes,
+        )
+    }
+
+    fn enum_node(
+        &self,
+        doc: String,
+        kind_constant: Option<String>,
+        type_name: String,
+        types: &[NodeType],
+    ) -> EnumNode {
+        let mut variant_names = BTreeSet::new();
+        let variants = types
+            .iter()
+            .map(|node_type| {
+                let mut name = variant_name(node_type);
+                if !variant_names.insert(name.clone()) {
+                    name = format!("{name}Token");
+                    variant_names.insert(name.clone());
+                }
+                let item_type = self.type_ref(node_type);
+                Variant {
+                    name,
+                    kind_pattern: self.kind_pattern(node_type),
+                    item_type: item_type.render(NODE_LIFETIME),
+                    item_name: item_type.name.clone(),
+                    is_super_type: self.is_super_type(node_type),
+                    method_suffix: method_name(&item_type.name),
+                }
+            })
+            .collect();
+        let borrowed = types
+            .iter()
+            .any(|node_type| self.type_ref(node_type).borrowed);
+        let mut kinds = Vec::new();
+        for node_type in types {
+            for kind in self.concrete_kinds(node_type) {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+        EnumNode {
+            doc,
+            kind_constant,
+            kinds,
+            method_suffix: method_name(&type_name),
+            type_name,
+            generics: if borrowed {
+                format!("<{NODE_LIFETIME}, 's>")
+            } else {
+                String::new()
+            },
+            variants,
+        }
+    }
+
+    fn operator_node(&self, doc: String, type_name: String, types: &[NodeType]) -> OperatorNode {
+        let mut variant_names = BTreeSet::new();
+        let variants = types
+            .iter()
+            .map(|node_type| {
+                let mut name = variant_name(node_type);
+                while !variant_names.insert(name.clone()) {
+                    name = format!("{name}Token");
+                }
+                OperatorVariant {
+                    name,
+                    kind_pattern: self.kind_pattern(node_type),
+                }
+            })
+            .collect::<Vec<_>>();
+        OperatorNode {
+            doc,
+            kinds: variants
+                .iter()
+                .map(|variant| variant.kind_pattern.clone())
+                .collect(),
+            method_suffix: method_name(&type_name),
+            type_name,
+            variants,
+        }
+    }
+
+    fn slot_type(&self, parent: &str, field: Option<&String>, children: &NodeChildren) -> TypeRef {
+        if let Some(type_ref) = self.choices.get(&(parent.to_string(), field.cloned())) {
+            return type_ref.clone();
+        }
+        match children.types().collect::<Vec<_>>().as_slice() {
+            [node_type] => self.type_ref(node_type),
+            _ => self.node_type_ref(ANY_NODE),
+        }
+    }
+
+    fn type_ref(&self, node_type: &NodeType) -> TypeRef {
+        if !node_type.is_named() {
+            return self.value_type_ref(TOKEN_VALUE);
+        }
+        match self.node_types.definition(node_type) {
+            Some(defn) if defn.kind().is_terminal() => {
+                self.value_type_ref(value_type_name(defn.node_type_name()))
+            }
+            Some(defn) if defn.kind().is_regular() && !defn.is_root() => {
+                self.node_type_ref(compound_type_name(defn.node_type_name()))
+            }
+            Some(defn) if defn.kind().is_super_type() => TypeRef {
+                name: type_name(defn.node_type_name()),
+                borrowed: defn
+                    .kind()
+                    .as_super_type()
+                    .map(|super_type| {
+                        super_type
+                            .subtypes()
+                            .any(|subtype| self.type_ref(subtype).borrowed)
+                    })
+                    .unwrap_or_default(),
+            },
+            _ => self.node_type_ref(ANY_NODE),
+        }
+    }
+
+    ///
+    /// A reference to a type wrapping a node, which has the lifetimes of the borrowed tree and
+    /// source unless the tree is shared.
+    ///
+    fn node_type_ref(&self, name: impl Into<String>) -> TypeRef {
+        TypeRef {
+            name: name.into(),
+            borrowed: !self.shared_tree,
+        }
+    }
+
+    fn value_type_ref(&self, name: impl Into<String>) -> TypeRef {
+        TypeRef {
+            name: name.into(),
+            borrowed: self.borrowed_values && !self.shared_tree,
+        }
+    }
+
+    fn value_generics(&self) -> String {
+        if self.borrowed_values && !self.shared_tree {
+            format!("<{NODE_LIFETIME}, 's>")
+        } else {
+            String::new()
+        }
+    }
+
+    ///
+    /// A pattern that matches the value of `Node::kind` for the given node type; named types use
+    /// the constants file whereas anonymous tokens are matched as string literals.
+    ///
+    fn kind_pattern(&self, node_type: &NodeType) -> String {
+        if !node_type.is_named() {
+            format!("{:?}", node_type.node_type())
+        } else if self.is_super_type(node_type) {
+            format!(
+                "nodes::SUPER_NODE_TYPE_{}",
+                constant_name(node_type.node_type())
+            )
+        } else {
+            format!("nodes::NODE_TYPE_{}", constant_name(node_type.node_type()))
+        }
+    }
+
+    ///
+    /// The patterns for all the node kinds that may actually appear in a tree for the given node
+    /// type, super-types are replaced by their sub-types.
+    ///
+    fn concrete_kinds(&self, node_type: &NodeType) -> Vec<String> {
+        self.concrete_types(node_type)
+            .iter()
+            .map(|node_type| self.kind_pattern(node_type))
+            .collect()
+    }
+
+    ///
+    /// All the node types that may actually appear in a tree for the given node type, super-types
+    /// are replaced by their sub-types.
+    ///
+    fn concrete_types(&self, node_type: &NodeType) -> Vec<NodeType> {
+        match self
+            .node_types
+            .definition(node_type)
+            .and_then(|defn| defn.kind().as_super_type())
+        {
+            Some(super_type) if node_type.is_named() => super_type
+                .subtypes()
+                .flat_map(|subtype| self.concrete_types(subtype))
+                .collect(),
+            _ => vec![node_type.clone()],
+        }
+    }
+
+    fn is_super_type(&self, node_type: &NodeType) -> bool {
+        self.node_types
+            .definition(node_type)
+            .map(|defn| defn.kind().is_super_type())
+            .unwrap_or_default()
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl TypeRef {
+    fn render(&self, lifetime: &str) -> String {
+        if self.borrowed {
+            format!("{}<{lifetime}, 's>", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+
+impl From<&NodeChildren> for Cardinality {
+    fn from(children: &NodeChildren) -> Self {
+        if children.is_multiple() {
+            Self::Multiple
+        } else if children.is_required() {
+            Self::Required
+        } else {
+            Self::Optional
+        }
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
 // Private Functions
 // ------------------------------------------------------------------------------------------------
 
+fn compound_type_name(kind: &str) -> String {
+    format!("{}Node", type_name(kind))
+}
+
+fn value_type_name(kind: &str) -> String {
+    format!("{}Value", type_name(kind))
+}
+
+///
+/// A slot accepting only anonymous tokens is represented by an operator enum.
+///
+fn is_operator(children: &NodeChildren) -> bool {
+    children.type_count() > 0 && children.types().all(|node_type| !node_type.is_named())
+}
+
+fn variant_name(node_type: &NodeType) -> String {
+    if node_type.is_named() {
+        type_name(node_type.node_type())
+    } else {
+        token_name(node_type.node_type())
+    }
+}
+
 // ------------------------------------------------------------------------------------------------
-// Modules
+// Unit Tests
 // ------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::writer::{ValueMode, WrapperFile, render_with};
+
+    fn render() -> String {
+        render_with(&WrapperFile, |arguments| arguments)
+    }
+
+    #[test]
+    fn test_root_node_accessors() {
+        let rendered = render();
+        assert!(rendered.contains("pub struct ModuleNode<'s>"));
+        assert!(rendered.contains("pub fn field_name(&self) -> IdentifierValue"));
+        assert!(rendered.contains("pub fn field_base(&self) -> Option<IriNode<'_, 's>>"));
+        assert!(rendered.contains("pub fn field_body(&self) -> ModuleBodyNode<'_, 's>"));
+    }
+
+    #[test]
+    fn test_compound_node_accessors() {
+        let rendered = render();
+        assert!(rendered.contains("pub struct EntityDefNode<'t, 's>"));
+        assert!(
+            rendered
+                .contains("pub fn field_from(&self) -> Option<FromDefinitionClauseNode<'t, 's>>")
+        );
+        assert!(rendered.contains(
+            "pub fn field_element(&self) -> FieldChildren<'t, 's, SequenceOfValuesElement<'t, 's>>"
+        ));
+    }
+
+    #[test]
+    fn test_super_type_enums() {
+        let rendered = render();
+        assert!(rendered.contains("pub enum Definition<'t, 's>"));
+        assert!(rendered.contains("    EntityDef(EntityDefNode<'t, 's>),"));
+        assert!(rendered.contains("pub enum SetOperator<'t, 's>"));
+        assert!(rendered.contains("    SetMembership(SetMembership<'t, 's>),"));
+        assert!(rendered.contains("pub enum InequalityRelation<'t, 's>"));
+        assert!(rendered.contains("    OpGreaterThan(OpGreaterThanValue),"));
+    }
+
+    #[test]
+    fn test_choice_enums() {
+        let rendered = render();
+        assert!(rendered.contains("pub enum ConstraintBody<'t, 's>"));
+        assert!(rendered.contains("    FormalConstraint(FormalConstraintNode<'t, 's>),"));
+        assert!(rendered.contains("pub enum PatternRestrictionFacetValue {"));
+        assert!(rendered.contains("    LeftBracket(TokenValue),"));
+        assert!(rendered.contains("pub enum ModuleBodyChild<'t, 's>"));
+        assert!(rendered.contains("    Definition(Definition<'t, 's>),"));
+    }
+
+    #[test]
+    fn test_shared_choice_enums() {
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_share_choices(true));
+        assert!(rendered.contains("pub enum AnnotationOrMember<'t, 's>"));
+        assert!(!rendered.contains("pub enum EntityBodyChild<'t, 's>"));
+        assert!(
+            rendered.contains(
+                "pub fn field_body(&self) -> FormalConstraintOrInformalConstraint<'t, 's>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_operator_enums() {
+        let rendered = render();
+        assert!(rendered.contains("pub enum DigitRestrictionFacetFacet {\n    FractionDigits,"));
+        assert!(rendered.contains("            Self::FractionDigits => \"fractionDigits\","));
+        assert!(rendered.contains("impl FromStr for DigitRestrictionFacetFacet {"));
+        assert!(rendered.contains("pub fn field_facet(&self) -> DigitRestrictionFacetFacet {"));
+        assert!(!rendered.contains("FractionDigits(TokenValue)"));
+    }
+
+    #[test]
+    fn test_keyword_accessors() {
+        let rendered = render();
+        assert!(rendered.contains("pub enum BooleanTruthVariant {\n    True,\n    U22A4,\n}"));
+        assert!(rendered.contains("    pub fn variant(&self) -> Option<BooleanTruthVariant> {"));
+        assert!(rendered.contains("fn keyword_child<'t>(node: Node<'t>, kinds: &[&str])"));
+        assert!(rendered.contains(
+            "    /// Returns `true` if this node contains the optional token `∉`.\n    pub fn has_token_literal(&self) -> bool {"
+        ));
+        assert!(!rendered.contains("has_u2209_token"));
+    }
+
+    #[test]
+    fn test_child_accessors() {
+        let rendered = render();
+        assert!(
+            rendered.contains("    pub fn child(&self) -> Option<ModuleVersionNode<'_, 's>> {")
+        );
+        assert!(rendered.contains("    pub fn child(&self) -> MemberDefNode<'t, 's> {"));
+        assert!(rendered.contains(
+            "    pub fn children(&self) -> UnnamedChildren<'t, 's, AnnotationNode<'t, 's>> {"
+        ));
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_fallible(true));
+        assert!(rendered.contains("Err(SdmlError::MissingChild {"));
+        assert!(!rendered.contains("unnamed_children"));
+    }
+
+    #[test]
+    fn test_parts_structs() {
+        let rendered = render();
+        assert!(rendered.contains(
+            "pub struct ModuleParts<'t, 's> {\n    pub name: IdentifierValue,\n    pub base: Option<IriNode<'t, 's>>,"
+        ));
+        assert!(rendered.contains("    pub fn parts(&self) -> ModuleParts<'_, 's> {"));
+        assert!(rendered.contains("    pub fn into_parts(self) -> AnnotationParts<'t, 's> {"));
+        assert!(!rendered.contains("pub struct BooleanTruthParts"));
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_fallible(true));
+        assert!(
+            rendered
+                .contains("    pub fn parts(&self) -> Result<ModuleParts<'_, 's>, SdmlError> {")
+        );
+        assert!(
+            rendered.contains("            children: self.children().collect::<Result<_, _>>()?,")
+        );
+    }
+
+    #[test]
+    fn test_shared_tree_wrappers() {
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_shared_tree(true));
+        assert!(rendered.contains("pub type SharedTree = Arc<(Tree, Arc<[u8]>)>;"));
+        assert!(rendered.contains("pub struct ModuleNode {\n    node: SharedNode,\n}"));
+        assert!(rendered.contains("        cursor.goto_descendant(self.descendant_index);"));
+        assert!(!rendered.contains("is not within its shared tree"));
+        assert!(rendered.contains("    fn from_tree(tree: Tree, source: Arc<[u8]>) -> Self {"));
+        assert!(rendered.contains("impl<'t, 's> TypedNode<'t, 's> for AnnotationNode {"));
+        assert!(rendered.contains("    pub fn into_parts(self) -> ModuleParts {"));
+        assert!(rendered.contains("pub trait Visitor {"));
+        assert!(!rendered.contains("pub struct ModuleBodyNode<'t, 's>"));
+    }
+
+    #[test]
+    fn test_fallible_accessors() {
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_fallible(true));
+        assert!(rendered.contains("pub enum SdmlError {"));
+        assert!(
+            rendered.contains("pub fn field_name(&self) -> Result<IdentifierValue, SdmlError>")
+        );
+        assert!(
+            rendered
+                .contains("pub fn field_base(&self) -> Result<Option<IriNode<'_, 's>>, SdmlError>")
+        );
+        assert!(!rendered.contains(".expect(\"Missing required field"));
+    }
+
+    #[test]
+    fn test_field_id_accessors() {
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_field_ids(true));
+        assert!(rendered.contains(
+            "        let child = child_by_field_id(self.node(), nodes::FieldName::Name)"
+        ));
+        assert!(rendered.contains("    field_id: Option<NonZeroU16>,"));
+        assert!(!rendered.contains("child_by_field_name"));
+        assert!(!rendered.contains("cursor.field_name().is_none()"));
+        assert!(rendered.contains(
+            "    let field_name = nodes::FieldName::Name.id(&cursor.node().language());"
+        ));
+        assert!(rendered.contains("                Some(field) if Some(field) == field_name => {"));
+    }
+
+    #[test]
+    fn test_kind_checked_construction() {
+        let rendered = render();
+        assert!(rendered.contains("pub const KIND: &'static str = nodes::NODE_TYPE_MODULE_BODY;"));
+        assert!(
+            rendered.contains(
+                "impl<'t, 's> TryFrom<(Node<'t>, &'s [u8])> for ModuleBodyNode<'t, 's> {"
+            )
+        );
+        assert!(
+            rendered
+                .contains("pub const KIND: &'static str = nodes::SUPER_NODE_TYPE_SET_OPERATOR;")
+        );
+        assert!(rendered.contains("pub trait NodeExt<'t> {"));
+        assert!(!rendered.contains("without checking that it can be represented"));
+        for type_name in ["ModuleBodyNode", "IdentifierValue"] {
+            let start = rendered
+                .find(&format!("impl<'t, 's> TypedNode<'t, 's> for {type_name}"))
+                .unwrap();
+            let from_node =
+                &rendered[start..][..rendered[start..].find("fn try_from_node").unwrap()];
+            assert!(from_node.contains(
+                "Self::try_from_node(node, source).unwrap_or_else(|error| panic!(\"{error}\"))"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_accessors_in_grammar_order() {
+        let rendered = render();
+        let position = |method: &str| {
+            rendered
+                .find(&format!("pub fn {method}(&self) -> "))
+                .unwrap_or_else(|| panic!("missing accessor {method}"))
+        };
+        let name = position("field_name");
+        assert!(name < position("field_base"));
+        assert!(position("field_base") < position("field_body"));
+        let datatype = rendered.find("pub struct DatatypeDefNode<'t, 's>").unwrap();
+        let accessors = [
+            "field_name",
+            "field_opaque",
+            "field_base",
+            "field_restriction",
+            "field_body",
+        ]
+        .map(|method| {
+            datatype
+                + rendered[datatype..]
+                    .find(&format!("pub fn {method}(&self) -> "))
+                    .unwrap()
+        });
+        assert!(accessors.windows(2).all(|pair| pair[0] < pair[1]));
+        let member_def = &rendered[rendered
+            .find("impl<'t, 's> MemberDefNode<'t, 's> {")
+            .unwrap()..];
+        let position = |accessor: &str| member_def.find(accessor).unwrap();
+        // `target` is a field of the inlined `_type_expression`, hoisted between `cardinality`
+        // and `body`, rather than sorted by name or placed after the fields found directly.
+        assert!(position("pub fn field_name(") < position("pub fn field_cardinality("));
+        assert!(position("pub fn field_cardinality(") < position("pub fn field_target("));
+        assert!(position("pub fn field_target(") < position("pub fn field_body("));
+    }
+
+    #[test]
+    fn test_typed_value_nodes() {
+        let rendered = render_with(&WrapperFile, |arguments| {
+            arguments.with_value_types([("integer", "i64"), ("decimal", "f64")])
+        });
+        assert!(
+            rendered.contains(
+                "pub fn value(&self) -> Result<i64, <i64 as ::std::str::FromStr>::Err> {"
+            )
+        );
+        assert!(
+            rendered.contains(
+                "pub fn value(&self) -> Result<f64, <f64 as ::std::str::FromStr>::Err> {"
+            )
+        );
+        assert_eq!(rendered.matches("pub fn value(&self)").count(), 2);
+    }
+
+    #[test]
+    fn test_borrowed_value_nodes() {
+        let rendered = render_with(&WrapperFile, |arguments| {
+            arguments.with_value_mode(ValueMode::Borrowed)
+        });
+        assert!(rendered.contains("pub struct IdentifierValue<'t, 's> {"));
+        assert!(rendered.contains("    node: Node<'t>,\n    text: &'s str,\n}"));
+        assert!(rendered.contains("pub fn text(&self) -> &'s str {\n        self.text\n    }"));
+        assert!(rendered.contains("            .map(|text| Self { node, text })"));
+        assert!(rendered.contains("impl From<IdentifierValue<'_, '_>> for String {"));
+        assert!(rendered.contains("pub fn field_name(&self) -> IdentifierValue<'_, 's>"));
+        assert!(rendered.contains("    OpGreaterThan(OpGreaterThanValue<'t, 's>),"));
+        assert!(!rendered.contains(".to_string(),"));
+    }
+
+    #[test]
+    fn test_visitor_and_walk_functions() {
+        let rendered = render();
+        assert!(rendered.contains("pub trait Visitor<'s> {"));
+        assert!(
+            rendered.contains(
+                "    fn visit_module_body_node(&mut self, node: &ModuleBodyNode<'_, 's>) {"
+            )
+        );
+        assert!(
+            rendered
+                .contains("    fn visit_identifier_value(&mut self, _node: &IdentifierValue) {}")
+        );
+        assert!(
+            rendered
+                .contains("pub fn walk_module_node<'s, V>(visitor: &mut V, node: &ModuleNode<'s>)")
+        );
+        assert!(rendered.contains("    visitor.visit_identifier_value(&node.field_name());"));
+        assert!(rendered.contains(
+            "        Definition::EntityDef(child) => visitor.visit_entity_def_node(child),"
+        ));
+    }
+
+    #[test]
+    fn test_fold_trait_and_functions() {
+        let rendered = render();
+        assert!(rendered.contains("pub trait Fold<'s> {"));
+        assert!(rendered.contains(
+            "    fn fold_module_body_node(&mut self, node: &ModuleBodyNode<'_, 's>) -> Self::Output {"
+        ));
+        assert!(rendered.contains("        self.fold_text(IdentifierValue::KIND, node.as_ref())"));
+        assert!(rendered.contains("                Some(nodes::FIELD_NAME) => {"));
+        assert!(rendered.contains(
+            "                    children.push(folder.fold_identifier_value(&IdentifierValue::from_node(child, source)));"
+        ));
+        assert!(rendered.contains("    folder.fold_node(ModuleBodyNode::KIND, children)"));
+        assert!(rendered.contains(
+            "        Definition::EntityDef(child) => folder.fold_entity_def_node(child),"
+        ));
+    }
+
+    #[test]
+    fn test_fallible_fold_returns_errors() {
+        let rendered = render_with(&WrapperFile, |arguments| arguments.with_fallible(true));
+        assert!(rendered.contains(
+            "    fn fold_module_body_node(&mut self, node: &ModuleBodyNode<'_, 's>) -> Result<Self::Output, SdmlError> {"
+        ));
+        assert!(
+            rendered
+                .contains("                Some(nodes::FIELD_NAME) if !child.is_missing() => {")
+        );
+        assert!(rendered.contains(
+            "                    children.push(folder.fold_identifier_value(&IdentifierValue::try_from_node(child, source)?)?);"
+        ));
+        assert!(rendered.contains("        node.field_name()?;"));
+        assert!(rendered.contains("    Ok(folder.fold_node(ModuleBodyNode::KIND, children))"));
+    }
+
+    #[test]
+    fn test_descendant_search() {
+        let rendered = render();
+        assert!(rendered.contains("pub struct Descendants<'t, 's, T> {"));
+        assert!(
+            rendered
+                .contains("    pub fn descendants_of<'t, T>(&'t self) -> Descendants<'t, 's, T>")
+        );
+        assert!(rendered.contains(
+            "    pub fn descendants_of_stopping_at<T, S>(&self) -> Descendants<'t, 's, T>"
+        ));
+        assert!(rendered.contains("    pub fn first_descendant_of<T>(&self) -> Option<T>"));
+    }
+
+    #[test]
+    fn test_spanned_nodes() {
+        let rendered = render();
+        assert!(rendered.contains("pub trait Spanned {"));
+        assert!(rendered.contains("impl Spanned for ModuleNode<'_> {"));
+        assert!(rendered.contains("impl Spanned for ModuleBodyNode<'_, '_> {"));
+        assert!(rendered.contains("impl Spanned for Definition<'_, '_> {"));
+        assert!(rendered.contains("impl Spanned for IdentifierValue {"));
+        assert!(rendered.contains("    fn eq_spanned(&self, other: &Self) -> bool"));
+        assert!(rendered.contains(
+            "impl PartialEq for IdentifierValue {\n    fn eq(&self, other: &Self) -> bool {\n        self.text == other.text\n"
+        ));
+        assert!(rendered.contains("    start_point: Point,"));
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let rendered = render();
+        assert!(rendered.contains("    pub fn syntax_errors(&self) -> SyntaxErrors<'_> {"));
+        assert!(rendered.contains("pub struct SyntaxError {"));
+        assert!(
+            rendered.contains(
+                "            return node.is_error()\n                || node.is_missing()"
+            )
+        );
+        assert!(rendered.contains(
+            "        (nodes::NODE_TYPE_ANNOTATION_MEMBER_DEF, _) => Some(&[nodes::NODE_TYPE_MEMBER_DEF]),"
+        ));
+        assert!(rendered.contains(
+            "        (nodes::NODE_TYPE_MODULE, Some(nodes::FIELD_NAME)) => Some(&[nodes::NODE_TYPE_IDENTIFIER]),"
+        ));
+    }
+
+    #[test]
+    fn test_comment_accessors() {
+        let rendered = render();
+        assert!(rendered.contains(
+            "pub const COMMENT_KINDS: &[&str] = &[\n    nodes::NODE_TYPE_LINE_COMMENT,\n];"
+        ));
+        assert!(rendered.contains("    pub fn leading_comments(&self) -> Vec<AnyNode<'t, 's>> {"));
+        assert!(rendered.contains("    pub fn trailing_comments(&self) -> Vec<AnyNode<'t, 's>> {"));
+    }
+
+    #[test]
+    fn test_parent_navigation() {
+        let rendered = render();
+        assert!(rendered.contains("pub enum AnnotationParent<'t, 's> {"));
+        assert!(rendered.contains("    EntityBody(EntityBodyNode<'t, 's>),"));
+        assert!(
+            rendered.contains("    pub fn parent(&self) -> Option<AnnotationParent<'t, 's>> {")
+        );
+        assert!(rendered.contains("    pub fn parent(&self) -> Option<PropertyDefNode<'t, 's>> {"));
+    }
+}
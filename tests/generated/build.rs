@@ -0,0 +1,84 @@
+/*!
+Generates the constants, wrapper, and AST files for the SDML grammar in `tests` into `OUT_DIR`,
+once for each set of options exercised by the tests, along with `generated.rs` declaring a module
+for each set.
+
+ */
+
+use cargo_tsgen::{
+    reader::{GrammarSources, InputFile, NodeTypesFile},
+    writer::{Arguments, AstFile, ConstantsFile, ForLanguage, Output, ValueMode, WrapperFile},
+};
+use std::{env, fs, path::PathBuf};
+
+type Configure = fn(Arguments<GrammarSources>) -> Arguments<GrammarSources>;
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let grammar_dir = manifest_dir.join("..");
+    let crate_dir = manifest_dir.join("../..");
+    println!(
+        "cargo::rerun-if-changed={}",
+        grammar_dir.join("grammar.json").display()
+    );
+    println!(
+        "cargo::rerun-if-changed={}",
+        grammar_dir.join("node-types.json").display()
+    );
+    println!(
+        "cargo::rerun-if-changed={}",
+        crate_dir.join("templates").display()
+    );
+
+    // templates are loaded relative to the current directory.
+    env::set_current_dir(&crate_dir).unwrap();
+
+    let grammar = GrammarSources::from_file(grammar_dir.join(GrammarSources::DEFAULT_FILE_NAME))
+        .expect("could not read the SDML grammar");
+    let node_types = NodeTypesFile::from_file(grammar_dir.join(NodeTypesFile::DEFAULT_FILE_NAME))
+        .expect("could not read the SDML node types");
+
+    let variants: [(&str, Configure); 3] = [
+        ("owned", |arguments| arguments),
+        ("fallible", |arguments| {
+            arguments
+                .with_fallible(true)
+                .with_value_mode(ValueMode::Borrowed)
+                .with_share_choices(true)
+                .with_field_ids(true)
+        }),
+        ("shared", |arguments| arguments.with_shared_tree(true)),
+    ];
+
+    let mut modules = String::new();
+    for (name, configure) in variants {
+        let dir = out_dir.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        let arguments = || configure(Arguments::new(grammar.clone(), ForLanguage::Rust, None));
+        ConstantsFile
+            .write_to_file(
+                Arguments::new(node_types.clone(), ForLanguage::Rust, None)
+                    .with_node_kinds(true)
+                    .with_field_ids(true)
+                    .with_kind_ids(true),
+                dir.join("nodes.rs"),
+            )
+            .unwrap();
+        WrapperFile
+            .write_to_file(arguments(), dir.join("wrapper.rs"))
+            .unwrap();
+        AstFile
+            .write_to_file(arguments().with_serde(true), dir.join("ast.rs"))
+            .unwrap();
+        modules.push_str(&format!("pub mod {name} {{\n"));
+        for module in ["nodes", "wrapper", "ast"] {
+            modules.push_str(&format!(
+                "    #[path = {:?}]\n    pub mod {module};\n",
+                dir.join(format!("{module}.rs")).display().to_string()
+            ));
+        }
+        modules.push_str("}\n");
+    }
+    fs::write(out_dir.join("generated.rs"), modules).unwrap();
+}
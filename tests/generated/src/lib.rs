@@ -0,0 +1,12 @@
+/*!
+The constants, wrapper, and AST files generated for the SDML grammar in `tests`, compiled and
+exercised against the `tree-sitter-sdml` parser; there is one module for each set of generator
+options.
+
+- `owned`; the default options.
+- `fallible`; fallible accessors, borrowed values, shared choices, and field ids.
+- `shared`; wrappers holding a shared tree.
+
+ */
+
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
@@ -0,0 +1,335 @@
+use generated_sdml::{fallible, owned, shared};
+use std::{sync::Arc, thread};
+use tree_sitter::{Parser, Tree};
+
+const TEST_SOURCE: &str = r#"module example <https://example.com/example#> is
+
+  entity Foo is
+    @skos:prefLabel = "Foo"@en
+    identity id -> string
+    name -> Thing
+    count -> {0..1} Thing
+  end
+
+end
+"#;
+
+fn parse(source: &str) -> Tree {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_sdml::LANGUAGE.into())
+        .expect("Error loading SDML grammar");
+    parser
+        .parse(source, None)
+        .expect("Could not parse test example")
+}
+
+#[test]
+fn test_owned_wrapper() {
+    use owned::wrapper::{Definition, ModuleBodyChild, ModuleNode, Spanned, TypedRootNode};
+
+    let module = ModuleNode::try_from_tree(parse(TEST_SOURCE), TEST_SOURCE.as_bytes()).unwrap();
+    assert_eq!(module.syntax_errors().count(), 0);
+    assert_eq!(module.field_name().as_ref(), "example");
+    assert_eq!(
+        module.field_base().unwrap().text(),
+        "<https://example.com/example#>"
+    );
+    let children = module.field_body().children().collect::<Vec<_>>();
+    assert_eq!(children.len(), 1);
+    let ModuleBodyChild::Definition(Definition::EntityDef(entity)) = &children[0] else {
+        panic!("expected an entity definition, not {:?}", children[0]);
+    };
+    assert_eq!(entity.field_name().as_ref(), "Foo");
+}
+
+#[test]
+fn test_owned_lowering() {
+    use owned::{
+        ast,
+        wrapper::{BuiltinTypesNode, ModuleNode, TypedRootNode},
+    };
+
+    let module = ModuleNode::from_tree(parse(TEST_SOURCE), TEST_SOURCE.as_bytes());
+    let builtin_types = module
+        .descendants_of::<BuiltinTypesNode<'_, '_>>()
+        .map(|node| ast::BuiltinTypes::from(&node))
+        .collect::<Vec<_>>();
+    let lowered = ast::Module::from(&module);
+    drop(module);
+    assert_eq!(lowered.name, ast::Identifier("example".to_string()));
+    assert_eq!(
+        lowered.base.map(|base| base.text),
+        Some("<https://example.com/example#>".to_string())
+    );
+    assert_eq!(lowered.body.children.len(), 1);
+    assert_eq!(
+        builtin_types
+            .iter()
+            .map(|builtin| (builtin.variant.clone(), builtin.text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some(ast::BuiltinTypesVariant::String), "string"),
+            (Some(ast::BuiltinTypesVariant::Thing), "Thing"),
+            (Some(ast::BuiltinTypesVariant::Thing), "Thing"),
+        ]
+    );
+}
+
+#[test]
+fn test_value_equality_ignores_position() {
+    use owned::wrapper::{IdentifierValue, ModuleNode, Spanned, TypedRootNode};
+
+    let source = include_str!("example.sdml");
+    let module = ModuleNode::from_tree(parse(source), source.as_bytes());
+    let names = module
+        .descendants_of::<IdentifierValue>()
+        .filter(|value| value.text() == "Name")
+        .collect::<Vec<_>>();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0], names[1]);
+    assert!(!names[0].eq_spanned(&names[1]));
+    assert!(names[0].eq_spanned(&names[0].clone()));
+}
+
+#[test]
+fn test_mismatched_kind_is_an_error() {
+    use owned::wrapper::{IdentifierValue, ModuleBodyNode, TypedNode};
+
+    let tree = parse(TEST_SOURCE);
+    let root = tree.root_node();
+    assert!(ModuleBodyNode::try_from_node(root, TEST_SOURCE.as_bytes()).is_err());
+    assert!(IdentifierValue::try_from_node(root, TEST_SOURCE.as_bytes()).is_err());
+}
+
+#[test]
+#[should_panic(expected = "module")]
+fn test_mismatched_kind_panics() {
+    use owned::wrapper::{ModuleBodyNode, TypedNode};
+
+    let tree = parse(TEST_SOURCE);
+    let _ = ModuleBodyNode::from_node(tree.root_node(), TEST_SOURCE.as_bytes());
+}
+
+#[test]
+fn test_fallible_wrapper() {
+    use fallible::wrapper::{ModuleNode, TypedRootNode};
+
+    let module = ModuleNode::try_from_tree(parse(TEST_SOURCE), TEST_SOURCE.as_bytes()).unwrap();
+    assert_eq!(module.field_name().unwrap().as_ref(), "example");
+    let body = module.field_body().unwrap();
+    assert!(body.children().all(|child| child.is_ok()));
+}
+
+#[test]
+fn test_shared_wrapper() {
+    use shared::wrapper::{ModuleNode, TypedRootNode};
+
+    let module =
+        ModuleNode::try_from_tree(parse(TEST_SOURCE), Arc::from(TEST_SOURCE.as_bytes())).unwrap();
+    let body = module.field_body();
+    let name = thread::spawn(move || {
+        let children = body.children().collect::<Vec<_>>();
+        assert_eq!(children.len(), 1);
+        module.field_name().as_ref().to_string()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(name, "example");
+}
+
+#[test]
+fn test_shared_nodes_found_again() {
+    use owned::wrapper::{Spanned as _, TypedRootNode as _};
+    use shared::wrapper::{IdentifierValue, ModuleNode, Spanned, TypedRootNode};
+
+    let source = include_str!("example.sdml");
+    let owned = owned::wrapper::ModuleNode::from_tree(parse(source), source.as_bytes());
+    let shared = ModuleNode::from_tree(parse(source), Arc::from(source.as_bytes()));
+    let expected = owned
+        .descendants_of::<owned::wrapper::IdentifierValue>()
+        .map(|value| (value.byte_range(), value.start_point(), value.end_point()))
+        .collect::<Vec<_>>();
+    let found = shared
+        .descendants_of::<IdentifierValue>()
+        .map(|value| (value.byte_range(), value.start_point(), value.end_point()))
+        .collect::<Vec<_>>();
+    assert!(!found.is_empty());
+    assert_eq!(found, expected);
+    assert_eq!(shared.start_point(), owned.start_point());
+    assert_eq!(shared.end_point(), owned.end_point());
+}
+
+#[test]
+fn test_fallible_invalid_utf8() {
+    use fallible::wrapper::{ModuleNode, SdmlError, TypedRootNode};
+
+    let tree = parse(TEST_SOURCE);
+    // the same tree over a source where the module name is no longer valid UTF-8.
+    let mut source = TEST_SOURCE.as_bytes().to_vec();
+    source[7] = 0xff;
+    let module = ModuleNode::try_from_tree(tree, &source).unwrap();
+    assert_eq!(
+        module.field_name(),
+        Err(SdmlError::InvalidUtf8 { byte_range: 7..14 })
+    );
+}
+
+#[derive(Default)]
+struct KindFolder {
+    entity_body: Vec<String>,
+}
+
+impl<'s> owned::wrapper::Fold<'s> for KindFolder {
+    type Output = String;
+
+    fn fold_node(&mut self, kind: &'static str, children: Vec<Self::Output>) -> Self::Output {
+        if kind == "entity_body" {
+            self.entity_body = children;
+        }
+        kind.to_string()
+    }
+
+    fn fold_text(&mut self, kind: &str, _: &str) -> Self::Output {
+        kind.to_string()
+    }
+}
+
+impl<'s> fallible::wrapper::Fold<'s> for KindFolder {
+    type Output = String;
+
+    fn fold_node(&mut self, kind: &'static str, children: Vec<Self::Output>) -> Self::Output {
+        if kind == "entity_body" {
+            self.entity_body = children;
+        }
+        kind.to_string()
+    }
+
+    fn fold_text(&mut self, kind: &str, _: &str) -> Self::Output {
+        kind.to_string()
+    }
+}
+
+#[test]
+fn test_owned_fold_in_source_order() {
+    use owned::wrapper::{Fold, ModuleNode, TypedRootNode};
+
+    let module = ModuleNode::from_tree(parse(TEST_SOURCE), TEST_SOURCE.as_bytes());
+    let mut folder = KindFolder::default();
+    assert_eq!(folder.fold_module_node(&module), "module");
+    assert_eq!(
+        folder.entity_body,
+        vec!["annotation", "entity_identity", "member", "member"]
+    );
+}
+
+#[test]
+fn test_fallible_fold() {
+    use fallible::wrapper::{Fold, ModuleNode, SdmlError, TypedRootNode};
+
+    let tree = parse(TEST_SOURCE);
+    let module = ModuleNode::try_from_tree(tree.clone(), TEST_SOURCE.as_bytes()).unwrap();
+    let mut folder = KindFolder::default();
+    assert_eq!(folder.fold_module_node(&module), Ok("module".to_string()));
+    assert_eq!(
+        folder.entity_body,
+        vec!["annotation", "entity_identity", "member", "member"]
+    );
+
+    let mut source = TEST_SOURCE.as_bytes().to_vec();
+    source[7] = 0xff;
+    let module = ModuleNode::try_from_tree(tree, &source).unwrap();
+    assert_eq!(
+        KindFolder::default().fold_module_node(&module),
+        Err(SdmlError::InvalidUtf8 { byte_range: 7..14 })
+    );
+}
+
+#[derive(Default)]
+struct EntityCounter {
+    entities: usize,
+}
+
+impl owned::ast::Fold for EntityCounter {
+    fn fold_entity_def_node(
+        &mut self,
+        node: &owned::wrapper::EntityDefNode<'_, '_>,
+    ) -> owned::ast::EntityDef {
+        self.entities += 1;
+        owned::ast::fold_entity_def_node(self, node)
+    }
+}
+
+struct UpperCaseIdentifiers;
+
+impl fallible::ast::Fold for UpperCaseIdentifiers {
+    fn fold_identifier_value(
+        &mut self,
+        node: &fallible::wrapper::IdentifierValue<'_, '_>,
+    ) -> fallible::ast::Identifier {
+        fallible::ast::Identifier(node.text().to_uppercase())
+    }
+}
+
+#[test]
+fn test_ast_fold() {
+    use owned::{
+        ast::{self, Fold},
+        wrapper::{ModuleNode, TypedRootNode},
+    };
+
+    let source = include_str!("example.sdml");
+    let module = ModuleNode::from_tree(parse(source), source.as_bytes());
+    let mut folder = EntityCounter::default();
+    assert_eq!(folder.fold_module_node(&module), ast::Module::from(&module));
+    assert_eq!(folder.entities, 1);
+}
+
+#[test]
+fn test_fallible_ast_fold() {
+    use fallible::{
+        ast::{self, Fold},
+        wrapper::{ModuleNode, TypedRootNode},
+    };
+
+    let source = include_str!("example.sdml");
+    let module = ModuleNode::from_tree(parse(source), source.as_bytes());
+    let folded = UpperCaseIdentifiers.fold_module_node(&module).unwrap();
+    assert_eq!(folded.name, ast::Identifier("EXAMPLE".to_string()));
+    assert_ne!(Ok(folded), ast::Module::try_from(&module));
+}
+
+#[test]
+fn test_syntax_errors_at_root() {
+    use owned::wrapper::{ModuleNode, TypedRootNode};
+
+    for source in ["module is end", "", "garbage ))) ("] {
+        let module = ModuleNode::from_tree(parse(source), source.as_bytes());
+        let errors = module.syntax_errors().collect::<Vec<_>>();
+        assert_eq!(errors.len(), 1, "errors in {source:?}: {errors:?}");
+        assert_eq!(errors[0].kind, "ERROR");
+        assert_eq!(errors[0].byte_range, 0..source.len());
+        assert_eq!(errors[0].parent_kind, None);
+    }
+}
+
+#[test]
+fn test_unparse_round_trip() {
+    use owned::{
+        ast::{self, Unparse},
+        wrapper::{ModuleNode, TypedRootNode},
+    };
+
+    let source = include_str!("example.sdml");
+    let module = ModuleNode::from_tree(parse(source), source.as_bytes());
+    assert_eq!(module.syntax_errors().count(), 0);
+    let lowered = ast::Module::from(&module);
+    let unparsed = lowered.to_source();
+    let reparsed = ModuleNode::from_tree(parse(&unparsed), unparsed.as_bytes());
+    assert_eq!(
+        reparsed.syntax_errors().collect::<Vec<_>>(),
+        Vec::new(),
+        "{unparsed}"
+    );
+    assert_eq!(ast::Module::from(&reparsed), lowered, "{unparsed}");
+}
@@ -1,3 +1,9 @@
+/*!
+This file contains all the node type names, node field names, and terminals,
+for the current grammar.
+
+ */
+
 // ---------------------------------------------------------------------------
 // Node name constants
 // ---------------------------------------------------------------------------
@@ -467,6 +473,72 @@ pub const NODE_TYPE_VALUE_VARIANT: &str = "value_variant";
 /// This constant holds the string name of the grammar node `variable`.
 pub const NODE_TYPE_VARIABLE: &str = "variable";
 
+/// This constant holds the string name of the grammar *terminal* node `comment_aligned`.
+pub const NODE_TYPE_COMMENT_ALIGNED: &str = "comment_aligned";
+
+/// This constant holds the string name of the grammar *terminal* node `comment_local`.
+pub const NODE_TYPE_COMMENT_LOCAL: &str = "comment_local";
+
+/// This constant holds the string name of the grammar *terminal* node `comment_page`.
+pub const NODE_TYPE_COMMENT_PAGE: &str = "comment_page";
+
+/// This constant holds the string name of the grammar *terminal* node `controlled_language_tag`.
+pub const NODE_TYPE_CONTROLLED_LANGUAGE_TAG: &str = "controlled_language_tag";
+
+/// This constant holds the string name of the grammar *terminal* node `decimal`.
+pub const NODE_TYPE_DECIMAL: &str = "decimal";
+
+/// This constant holds the string name of the grammar *terminal* node `double`.
+pub const NODE_TYPE_DOUBLE: &str = "double";
+
+/// This constant holds the string name of the grammar *terminal* node `hex_byte`.
+pub const NODE_TYPE_HEX_BYTE: &str = "hex_byte";
+
+/// This constant holds the string name of the grammar *terminal* node `identifier`.
+pub const NODE_TYPE_IDENTIFIER: &str = "identifier";
+
+/// This constant holds the string name of the grammar *terminal* node `integer`.
+pub const NODE_TYPE_INTEGER: &str = "integer";
+
+/// This constant holds the string name of the grammar *terminal* node `kw_is_fixed`.
+pub const NODE_TYPE_KW_IS_FIXED: &str = "kw_is_fixed";
+
+/// This constant holds the string name of the grammar *terminal* node `language_tag`.
+pub const NODE_TYPE_LANGUAGE_TAG: &str = "language_tag";
+
+/// This constant holds the string name of the grammar *terminal* node `math_op_subtract`.
+pub const NODE_TYPE_MATH_OP_SUBTRACT: &str = "math_op_subtract";
+
+/// This constant holds the string name of the grammar *terminal* node `op_greater_than`.
+pub const NODE_TYPE_OP_GREATER_THAN: &str = "op_greater_than";
+
+/// This constant holds the string name of the grammar *terminal* node `opaque`.
+pub const NODE_TYPE_OPAQUE: &str = "opaque";
+
+/// This constant holds the string name of the grammar *terminal* node `quoted_string`.
+pub const NODE_TYPE_QUOTED_STRING: &str = "quoted_string";
+
+/// This constant holds the string name of the grammar *terminal* node `rational`.
+pub const NODE_TYPE_RATIONAL: &str = "rational";
+
+/// This constant holds the string name of the grammar *terminal* node `reserved_self`.
+pub const NODE_TYPE_RESERVED_SELF: &str = "reserved_self";
+
+/// This constant holds the string name of the grammar *terminal* node `seq_builder_separator`.
+pub const NODE_TYPE_SEQ_BUILDER_SEPARATOR: &str = "seq_builder_separator";
+
+/// This constant holds the string name of the grammar *terminal* node `unknown_type`.
+pub const NODE_TYPE_UNKNOWN_TYPE: &str = "unknown_type";
+
+/// This constant holds the string name of the grammar *terminal* node `unsigned`.
+pub const NODE_TYPE_UNSIGNED: &str = "unsigned";
+
+/// This constant holds the string name of the grammar *terminal* node `value_empty_sequence`.
+pub const NODE_TYPE_VALUE_EMPTY_SEQUENCE: &str = "value_empty_sequence";
+
+/// This constant holds the string name of the grammar *terminal* node `wildcard`.
+pub const NODE_TYPE_WILDCARD: &str = "wildcard";
+
 // ---------------------------------------------------------------------------
 // Field name constants
 // ---------------------------------------------------------------------------